@@ -0,0 +1,31 @@
+use clay_rs_wgpu::ui::ui_clipboard::Clipboard;
+
+/// The system clipboard, for the selection to copy to.
+pub struct SystemClipboard {
+    clipboard: arboard::Clipboard,
+}
+
+impl SystemClipboard {
+    /// Fails on systems without a clipboard, like a linux session without a display server.
+    pub fn new() -> Option<Self> {
+        match arboard::Clipboard::new() {
+            Ok(clipboard) => Some(Self { clipboard }),
+            Err(error) => {
+                log::warn!("no system clipboard available: {}", error);
+                None
+            }
+        }
+    }
+}
+
+impl Clipboard for SystemClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.clipboard.get_text().ok()
+    }
+
+    fn set_text(&mut self, text: &str) {
+        if let Err(error) = self.clipboard.set_text(text) {
+            log::warn!("could not copy to the system clipboard: {}", error);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use winit::keyboard::{Key, ModifiersState};
+
+/// A key combination that runs a command.
+///
+/// Character keys match regardless of case, so holding Shift only counts when `shift` is set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Accelerator {
+    /// Ctrl, or Cmd on macOS
+    pub shortcut: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub key: Key,
+}
+
+impl Accelerator {
+    /// Ctrl (or Cmd) with the character `key`.
+    pub fn shortcut(key: &str) -> Self {
+        Self {
+            shortcut: true,
+            shift: false,
+            alt: false,
+            key: Key::Character(key.into()),
+        }
+    }
+
+    pub fn matches(&self, key: &Key, modifiers: ModifiersState) -> bool {
+        let keys_match = match (&self.key, key) {
+            (Key::Character(expected), Key::Character(pressed)) => expected.eq_ignore_ascii_case(pressed),
+            (expected, pressed) => expected == pressed,
+        };
+
+        keys_match
+            && self.shortcut == (modifiers.control_key() || modifiers.super_key())
+            && self.shift == modifiers.shift_key()
+            && self.alt == modifiers.alt_key()
+    }
+
+    /// How the combination is written next to menu items, like "Ctrl+N".
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.shortcut {
+            label.push_str(if cfg!(target_os = "macos") { "Cmd+" } else { "Ctrl+" });
+        }
+        if self.shift {
+            label.push_str("Shift+");
+        }
+        if self.alt {
+            label.push_str("Alt+");
+        }
+        match &self.key {
+            Key::Character(character) => label.push_str(&character.to_uppercase()),
+            key => label.push_str(&format!("{:?}", key)),
+        }
+        label
+    }
+}
+
+/// Something the user can do, from a menu or with its accelerator.
+pub struct Command<T> {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub accelerator: Option<Accelerator>,
+    /// the label of the accelerator, kept here for menus to borrow while they are laid out
+    pub accelerator_label: Option<String>,
+    pub action: fn(&mut T),
+}
+
+/// The commands of the application, which menus and keyboard shortcuts both run by id.
+///
+/// Actions are plain functions of the state they change, so the registry can live inside
+/// that state and be looked up before the action borrows it.
+pub struct CommandRegistry<T> {
+    commands: Vec<Command<T>>,
+}
+
+impl<T> CommandRegistry<T> {
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Adds a command, replacing any registered under the same id.
+    pub fn register(&mut self, id: &'static str, label: &'static str, accelerator: Option<Accelerator>, action: fn(&mut T)) {
+        self.commands.retain(|command| command.id != id);
+        let accelerator_label = accelerator.as_ref().map(Accelerator::label);
+        self.commands.push(Command { id, label, accelerator, accelerator_label, action });
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Command<T>> {
+        self.commands.iter().find(|command| command.id == id)
+    }
+
+    /// The action of the command `id`, to call once the registry is no longer borrowed.
+    pub fn action(&self, id: &str) -> Option<fn(&mut T)> {
+        self.get(id).map(|command| command.action)
+    }
+
+    /// The id of the command whose accelerator a key press matches.
+    pub fn command_for_key(&self, key: &Key, modifiers: ModifiersState) -> Option<&'static str> {
+        self.commands.iter()
+            .find(|command| command.accelerator.as_ref().is_some_and(|accelerator| accelerator.matches(key, modifiers)))
+            .map(|command| command.id)
+    }
+}
+
+impl<T> Default for CommandRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
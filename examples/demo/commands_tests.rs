@@ -0,0 +1,125 @@
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use clay_rs_wgpu::testing::snapshot::{render_snapshot, SnapshotConfig};
+
+use crate::commands::{Accelerator, CommandRegistry};
+use crate::ui_layout::{declare_layout, initialize_user_data, ClayState, COMMAND_CLOSE_DOCUMENT};
+
+fn registry() -> CommandRegistry<Vec<&'static str>> {
+    let mut commands = CommandRegistry::new();
+    commands.register("new", "New", Some(Accelerator::shortcut("n")), |log| log.push("new"));
+    commands.register("save_as", "Save As", Some(Accelerator { shift: true, ..Accelerator::shortcut("s") }), |log| log.push("save_as"));
+    commands.register("about", "About", None, |log| log.push("about"));
+    commands
+}
+
+#[test]
+fn accelerators_need_their_exact_modifiers() {
+    let commands = registry();
+
+    assert_eq!(commands.command_for_key(&Key::Character("n".into()), ModifiersState::CONTROL), Some("new"));
+    assert_eq!(commands.command_for_key(&Key::Character("n".into()), ModifiersState::SUPER), Some("new"));
+    assert_eq!(commands.command_for_key(&Key::Character("n".into()), ModifiersState::empty()), None);
+    assert_eq!(commands.command_for_key(&Key::Character("N".into()), ModifiersState::CONTROL | ModifiersState::SHIFT), None);
+    assert_eq!(commands.command_for_key(&Key::Character("S".into()), ModifiersState::CONTROL | ModifiersState::SHIFT), Some("save_as"));
+    assert_eq!(commands.command_for_key(&Key::Named(NamedKey::Enter), ModifiersState::CONTROL), None);
+}
+
+#[test]
+fn menus_and_shortcuts_run_the_same_action() {
+    let commands = registry();
+    let mut log = Vec::new();
+
+    let id = commands.command_for_key(&Key::Character("n".into()), ModifiersState::CONTROL).unwrap();
+    commands.action(id).unwrap()(&mut log);
+    commands.action("new").unwrap()(&mut log);
+    assert!(commands.action("missing").is_none());
+
+    assert_eq!(log, ["new", "new"]);
+}
+
+#[test]
+fn registering_an_id_again_replaces_the_command() {
+    let mut commands = registry();
+    commands.register("new", "New Window", None, |log| log.push("new_window"));
+
+    let mut log = Vec::new();
+    commands.action("new").unwrap()(&mut log);
+
+    assert_eq!(commands.get("new").unwrap().label, "New Window");
+    assert_eq!(commands.get("new").unwrap().accelerator_label, None);
+    assert_eq!(commands.command_for_key(&Key::Character("n".into()), ModifiersState::CONTROL), None);
+    assert_eq!(log, ["new_window"]);
+}
+
+#[test]
+fn accelerator_labels() {
+    let ctrl = if cfg!(target_os = "macos") { "Cmd" } else { "Ctrl" };
+
+    assert_eq!(Accelerator::shortcut("w").label(), format!("{}+W", ctrl));
+    assert_eq!(Accelerator { shift: true, ..Accelerator::shortcut("s") }.label(), format!("{}+Shift+S", ctrl));
+    assert_eq!(registry().get("save_as").unwrap().accelerator_label, Some(format!("{}+Shift+S", ctrl)));
+}
+
+#[test]
+fn ctrl_w_closes_the_selected_document() {
+    let mut user_data = ClayState::default();
+    initialize_user_data(&mut user_data);
+    let documents = user_data.documents.len();
+    user_data.selected_document_index = documents - 1;
+
+    let id = user_data.commands.command_for_key(&Key::Character("w".into()), ModifiersState::CONTROL).unwrap();
+    assert_eq!(id, COMMAND_CLOSE_DOCUMENT);
+    user_data.run_command(id);
+
+    assert_eq!(user_data.documents.len(), documents - 1);
+    assert_eq!(user_data.selected_document_index, documents - 2);
+}
+
+/// Lays out one frame, the way the demo registers focusable elements.
+fn frame(user_data: &mut ClayState) {
+    render_snapshot(&SnapshotConfig { size: (800, 600), ..Default::default() }, |clay| {
+        user_data.focus.begin_frame();
+        declare_layout(clay, user_data);
+        user_data.focus.end_frame();
+    });
+}
+
+#[test]
+fn the_file_menu_runs_commands_from_the_keyboard() {
+    let mut user_data = ClayState::default();
+    initialize_user_data(&mut user_data);
+    let documents = user_data.documents.len();
+    frame(&mut user_data);
+
+    // the file button comes first, enter opens its menu
+    user_data.focus.focus_next();
+    user_data.focus.activate();
+    frame(&mut user_data);
+    assert!(user_data.file_menu_open);
+
+    // the menu items come right after it, the first one adds a document
+    user_data.focus.focus_next();
+    frame(&mut user_data);
+    assert!(user_data.file_menu_open);
+    user_data.focus.activate();
+    frame(&mut user_data);
+
+    assert_eq!(user_data.documents.len(), documents + 1);
+    assert!(!user_data.file_menu_open);
+}
+
+#[test]
+fn header_buttons_run_their_command_from_the_keyboard() {
+    let mut user_data = ClayState::default();
+    initialize_user_data(&mut user_data);
+    frame(&mut user_data);
+
+    // past the file button, edit renames the selected document
+    user_data.focus.focus_next();
+    user_data.focus.focus_next();
+    user_data.focus.activate();
+    frame(&mut user_data);
+
+    assert_eq!(user_data.rename.as_ref().map(|rename| rename.document_index), Some(user_data.selected_document_index));
+}
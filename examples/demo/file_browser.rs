@@ -0,0 +1,120 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Files the browser offers to open, by extension.
+const DOCUMENT_EXTENSIONS: [&str; 3] = ["txt", "md", "markdown"];
+
+/// A directory or a document in the directory being browsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileEntry {
+    pub name: String,
+    /// the name as shown in the browser, directories end in a slash, kept for the layout to borrow
+    pub label: String,
+    pub path: PathBuf,
+    pub is_directory: bool,
+}
+
+/// The contents of one directory, for picking a document to open.
+///
+/// Only directories and plain text or markdown files are listed, directories first and
+/// each sorted by name. Hidden entries are left out.
+pub struct FileBrowser {
+    directory: PathBuf,
+    /// the directory as shown above the entries, kept for the layout to borrow
+    directory_label: String,
+    entries: Vec<FileEntry>,
+    error: Option<String>,
+}
+
+impl FileBrowser {
+    /// A browser showing `directory`. When it can't be read, the browser shows why instead.
+    pub fn new(directory: &Path) -> Self {
+        let mut browser = Self {
+            directory: directory.to_path_buf(),
+            directory_label: String::new(),
+            entries: Vec::new(),
+            error: None,
+        };
+        browser.refresh();
+        browser
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn directory_label(&self) -> &str {
+        &self.directory_label
+    }
+
+    pub fn entries(&self) -> &[FileEntry] {
+        &self.entries
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Shows `directory` instead.
+    pub fn navigate(&mut self, directory: &Path) {
+        self.directory = directory.to_path_buf();
+        self.refresh();
+    }
+
+    /// Shows the directory above this one, if there is one.
+    pub fn navigate_up(&mut self) {
+        if let Some(parent) = self.directory.parent().map(Path::to_path_buf) {
+            self.navigate(&parent);
+        }
+    }
+
+    /// Reads the directory again.
+    pub fn refresh(&mut self) {
+        self.directory_label = self.directory.display().to_string();
+        match list_directory(&self.directory) {
+            Ok(entries) => {
+                self.entries = entries;
+                self.error = None;
+            }
+            Err(error) => {
+                self.entries.clear();
+                self.error = Some(format!("could not read {}: {}", self.directory.display(), error));
+            }
+        }
+    }
+}
+
+pub fn is_document(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| DOCUMENT_EXTENSIONS.iter().any(|known| known.eq_ignore_ascii_case(extension)))
+}
+
+fn list_directory(directory: &Path) -> io::Result<Vec<FileEntry>> {
+    let mut entries = Vec::new();
+
+    for entry in std::fs::read_dir(directory)? {
+        // entries that vanish or can't be inspected while listing are skipped
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+
+        let path = entry.path();
+        // follows symlinks, so a link to a directory can be browsed into
+        let is_directory = path.is_dir();
+        if is_directory || is_document(&path) {
+            let label = match is_directory {
+                true => format!("{}/", name),
+                false => name.clone(),
+            };
+            entries.push(FileEntry { name, label, path, is_directory });
+        }
+    }
+
+    entries.sort_by(|a, b| b.is_directory.cmp(&a.is_directory).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    Ok(entries)
+}
@@ -0,0 +1,97 @@
+use crate::file_browser::FileBrowser;
+use crate::scratch_directory::ScratchDirectory;
+use crate::ui_layout::{BrowserAction, ClayState};
+
+#[test]
+fn lists_directories_first_and_only_documents() {
+    let directory = ScratchDirectory::new("listing");
+    std::fs::create_dir(directory.join("notes")).unwrap();
+    std::fs::write(directory.join("b.md"), "# B").unwrap();
+    std::fs::write(directory.join("A.txt"), "a").unwrap();
+    std::fs::write(directory.join("image.png"), []).unwrap();
+    std::fs::write(directory.join(".hidden.txt"), "").unwrap();
+
+    let browser = FileBrowser::new(&directory);
+    let names = browser.entries().iter().map(|entry| entry.name.as_str()).collect::<Vec<_>>();
+
+    assert_eq!(names, ["notes", "A.txt", "b.md"]);
+    assert_eq!(browser.entries()[0].label, "notes/");
+    assert_eq!(browser.entries()[1].label, "A.txt");
+    assert!(browser.entries()[0].is_directory);
+    assert_eq!(browser.error(), None);
+}
+
+#[test]
+fn navigating_into_and_out_of_directories() {
+    let directory = ScratchDirectory::new("navigate");
+    std::fs::create_dir(directory.join("notes")).unwrap();
+    std::fs::write(directory.join("notes").join("acorns.md"), "").unwrap();
+
+    let mut browser = FileBrowser::new(&directory);
+    browser.navigate(&directory.join("notes"));
+    assert_eq!(browser.entries()[0].name, "acorns.md");
+
+    browser.navigate_up();
+    assert_eq!(browser.directory(), directory.path());
+    assert_eq!(browser.directory_label(), directory.display().to_string());
+}
+
+#[test]
+fn a_missing_directory_shows_an_error() {
+    let scratch = ScratchDirectory::new("missing");
+    let directory = scratch.join("gone");
+
+    let browser = FileBrowser::new(&directory);
+
+    assert!(browser.entries().is_empty());
+    assert!(browser.error().is_some());
+}
+
+#[test]
+fn opening_a_document_twice_selects_the_open_one() {
+    let directory = ScratchDirectory::new("open");
+    std::fs::write(directory.join("squirrels.md"), "# Squirrels").unwrap();
+    std::fs::write(directory.join("acorns.txt"), "Acorns").unwrap();
+
+    let mut user_data = ClayState::default();
+    user_data.open_paths(&[directory.join("squirrels.md"), directory.join("missing.txt"), directory.join("acorns.txt")]);
+    assert_eq!(user_data.documents.len(), 2);
+    assert_eq!(user_data.documents[0].title, "squirrels");
+    assert_eq!(user_data.selected_document_index, 0);
+
+    user_data.open_path(&directory.join(".").join("acorns.txt")).unwrap();
+
+    assert_eq!(user_data.documents.len(), 2);
+    assert_eq!(user_data.selected_document_index, 1);
+}
+
+#[test]
+fn closing_every_document_leaves_nothing_selected_out_of_range() {
+    let mut user_data = ClayState::default();
+    user_data.new_document();
+    user_data.new_document();
+
+    user_data.close_document();
+    user_data.close_document();
+    user_data.close_document();
+
+    assert!(user_data.documents.is_empty());
+    assert_eq!(user_data.selected_document_index, 0);
+}
+
+#[test]
+fn browser_actions_wait_for_the_next_layout() {
+    let directory = ScratchDirectory::new("deferred");
+    std::fs::create_dir(directory.join("notes")).unwrap();
+
+    let mut user_data = ClayState::default();
+    user_data.file_browser = Some(FileBrowser::new(&directory));
+    user_data.browser_action = Some(BrowserAction::Navigate(directory.join("notes")));
+    // the frame that picked it is still drawn from the browser as it was
+    assert_eq!(user_data.file_browser.as_ref().unwrap().directory(), directory.path());
+
+    user_data.run_browser_action();
+
+    assert_eq!(user_data.file_browser.as_ref().unwrap().directory(), directory.join("notes"));
+    assert!(user_data.browser_action.is_none());
+}
@@ -4,9 +4,12 @@ use winit::window::Window;
 
 use clay_layout::Clay;
 
-use crate::ui::ui_renderer::UIState;
-use crate::ui::ui_layout::{self, create_layout, ClayState};
-use super::depth_texture::DepthTexture;
+use clay_rs_wgpu::graphics::depth_texture::DepthTexture;
+use clay_rs_wgpu::ui::ui_fonts::UIFont;
+use clay_rs_wgpu::ui::ui_text::measure_text;
+use clay_rs_wgpu::UIState;
+
+use crate::ui_layout::{self, create_layout, ClayState};
 
 pub struct GraphicsContext<'a>{
     #[allow(dead_code)]
@@ -69,14 +72,22 @@ impl<'a> GraphicsContext<'a> {
 
         surface.configure(&device, &config);
 
-        let depth_texture = DepthTexture::new(&device, &config);
+        let depth_texture = DepthTexture::new(&device, (config.width, config.height));
 
-        let ui_state = Rc::<RefCell<UIState>>::new(RefCell::new(UIState::new(&device, &queue,config.format, window.inner_size())));
+        let ui_state = Rc::<RefCell<UIState>>::new(RefCell::new(UIState::new(&device, &queue,config.format, (config.width, config.height))));
+        {
+            let mut ui = ui_state.borrow_mut();
+            ui.ui_text.register_font(ui_layout::FONT_ID_TITLE, UIFont::sans_serif().bold());
+            ui.ui_text.register_font(ui_layout::FONT_ID_MONOSPACE, UIFont::monospace());
+            ui.ui_text.register_font(ui_layout::FONT_ID_PREEDIT, UIFont::sans_serif().underline());
+            ui.ui_text.register_font(ui_layout::FONT_ID_ITALIC, UIFont::sans_serif().italic());
+        }
 
         let mut clay = Clay::new((size.0 as f32, size.1 as f32).into());
         
-        clay.set_measure_text_function_user_data(ui_state.clone(), ui_layout::measure_text);
+        clay.set_measure_text_function_user_data(ui_state.clone(), measure_text);
         let mut clay_user_data = ClayState::default();
+        clay_user_data.ui_state = Some(ui_state.clone());
         ui_layout::initialize_user_data(&mut clay_user_data);
 
         Self {
@@ -137,7 +148,9 @@ impl<'a> GraphicsContext<'a> {
                 &mut self.clay_user_data,
                 0.016,
             );
-            self.ui_state.borrow_mut().render_clay(render_commands, &mut render_pass, &self.device, &self.queue, &self.config);
+            let mut ui_state = self.ui_state.borrow_mut();
+            ui_state.focus_ring.element_id = self.clay_user_data.focus.focused();
+            ui_state.render_clay(render_commands, &mut render_pass, &self.device, &self.queue, (self.config.width, self.config.height));
         }
 
         self.queue.submit(std::iter::once(command_encoder.finish()));
@@ -148,8 +161,6 @@ impl<'a> GraphicsContext<'a> {
     pub fn resize(&mut self) {
         let new_size = (self.window.inner_size().width as i32, self.window.inner_size().height as i32);
 
-        self.ui_state.borrow_mut().resize(new_size);
-
         self.clay_user_data.size = (new_size.0 as f32, new_size.1 as f32);
 
         if new_size.0 > 0 && new_size.1 > 0 {
@@ -159,7 +170,7 @@ impl<'a> GraphicsContext<'a> {
             self.surface.configure(&self.device, &self.config);
         }
 
-        self.depth_texture = DepthTexture::new(&self.device, &self.config);
+        self.depth_texture = DepthTexture::new(&self.device, (self.config.width, self.config.height));
     }
 
     pub fn _update_surface(&mut self){
@@ -0,0 +1,65 @@
+use image::RgbaImage;
+
+use clay_rs_wgpu::testing::snapshot::{assert_snapshot_with_state, assert_software_snapshot, render_snapshot_with_state, SnapshotConfig};
+
+use crate::markdown;
+use crate::ui_layout::{declare_layout, initialize_user_data, markdown_styles, ClayState, Document};
+
+fn demo_state() -> ClayState {
+    let mut user_data = ClayState::default();
+    initialize_user_data(&mut user_data);
+    user_data
+}
+
+#[test]
+fn snapshot_demo_layout() {
+    let config = SnapshotConfig {
+        size: (800, 600),
+        // glyph rasterization differs slightly between adapters
+        tolerance: 8,
+        max_mismatched_pixels: 200,
+        ..Default::default()
+    };
+    let mut user_data = demo_state();
+    assert_snapshot_with_state("demo_layout", &config, |clay, ui_state| {
+        // without it markdown spans are drawn plain
+        user_data.ui_state = Some(ui_state.clone());
+        declare_layout(clay, &mut user_data);
+    });
+}
+
+#[test]
+fn software_snapshot_demo_layout() {
+    let config = SnapshotConfig {
+        size: (800, 600),
+        ..Default::default()
+    };
+    let mut user_data = demo_state();
+    assert_software_snapshot("software_demo_layout", &config, |clay| declare_layout(clay, &mut user_data));
+}
+
+/// Whether any pixel is within `tolerance` of `color` in every channel.
+fn has_color(image: &RgbaImage, color: [u8; 3], tolerance: u8) -> bool {
+    image.pixels().any(|pixel| (0..3).all(|channel| pixel.0[channel].abs_diff(color[channel]) <= tolerance))
+}
+
+#[test]
+fn markdown_spans_are_drawn_in_their_style() {
+    let mut user_data = demo_state();
+    user_data.documents.push(Document {
+        title: "Spans".to_string(),
+        blocks: markdown::parse("Call `measure_text` **twice**.", &markdown_styles()),
+        path: None,
+        scroll_offset: (0.0, 0.0),
+    });
+    user_data.selected_document_index = user_data.documents.len() - 1;
+
+    let image = render_snapshot_with_state(&SnapshotConfig { size: (800, 600), ..Default::default() }, |clay, ui_state| {
+        user_data.ui_state = Some(ui_state.clone());
+        declare_layout(clay, &mut user_data);
+    });
+
+    // the inline code is the only thing on the background of code blocks, in its own color
+    assert!(has_color(&image, [50, 50, 56], 1));
+    assert!(has_color(&image, [230, 180, 90], 8));
+}
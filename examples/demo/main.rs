@@ -0,0 +1,42 @@
+// uncomment to hide the shell on windows systems
+//#![windows_subsystem = "windows"]
+
+use std::path::PathBuf;
+
+use winit::event_loop::{ControlFlow, EventLoop};
+
+mod windowing;
+mod clipboard;
+mod graphics_context;
+mod ui_layout;
+mod text_input;
+mod commands;
+mod file_browser;
+mod session;
+mod markdown;
+#[cfg(test)]
+mod scratch_directory;
+#[cfg(test)]
+mod layout_snapshots;
+#[cfg(test)]
+mod text_input_tests;
+#[cfg(test)]
+mod commands_tests;
+#[cfg(test)]
+mod file_browser_tests;
+#[cfg(test)]
+mod session_tests;
+#[cfg(test)]
+mod markdown_tests;
+
+fn main() {
+    let event_loop = match EventLoop::new() {
+        Ok(event_loop) => event_loop,
+        Err(_) => return
+    };
+    event_loop.set_control_flow(ControlFlow::Wait);
+
+    // documents to open can be given on the command line
+    let paths = std::env::args_os().skip(1).map(PathBuf::from).collect();
+    event_loop.run_app(&mut windowing::App::new(paths)).unwrap();
+}
\ No newline at end of file
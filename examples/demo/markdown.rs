@@ -0,0 +1,204 @@
+use clay_rs_wgpu::ui::ui_rich_text::{RichText, SpanStyle};
+
+/// How inline markup is drawn within a paragraph.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MarkdownStyles {
+    pub code: SpanStyle,
+    pub strong: SpanStyle,
+    pub emphasis: SpanStyle,
+}
+
+/// An item of a list, with the bullet or number in front of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListItem {
+    pub marker: String,
+    pub text: RichText,
+}
+
+/// A block of a markdown document, with its inline markup turned into rich text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Block {
+    Heading { level: u8, text: RichText },
+    Paragraph(RichText),
+    List(Vec<ListItem>),
+    Quote(Vec<Block>),
+    Code { language: Option<String>, code: String },
+    Rule,
+}
+
+/// Parses the markdown most documents use: ATX headings, paragraphs, bullet and numbered
+/// lists, block quotes, fenced code blocks and horizontal rules, with inline code, strong
+/// and emphasized text. Lists don't nest and anything else is kept as text.
+pub fn parse(text: &str, styles: &MarkdownStyles) -> Vec<Block> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let mut blocks = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index].trim();
+
+        if line.is_empty() {
+            index += 1;
+        } else if let Some((fence, language)) = code_fence(line) {
+            let mut code = Vec::new();
+            index += 1;
+            while index < lines.len() && !lines[index].trim().starts_with(fence) {
+                code.push(lines[index]);
+                index += 1;
+            }
+            // past the closing fence, an unclosed block runs to the end of the document
+            index += 1;
+            blocks.push(Block::Code {
+                language,
+                code: code.join("\n"),
+            });
+        } else if is_rule(line) {
+            blocks.push(Block::Rule);
+            index += 1;
+        } else if let Some((level, heading)) = heading(line) {
+            blocks.push(Block::Heading { level, text: parse_inline(heading, styles) });
+            index += 1;
+        } else if line.starts_with('>') {
+            let mut quoted = Vec::new();
+            while index < lines.len() {
+                let Some(rest) = lines[index].trim().strip_prefix('>') else {
+                    break;
+                };
+                quoted.push(rest.strip_prefix(' ').unwrap_or(rest));
+                index += 1;
+            }
+            blocks.push(Block::Quote(parse(&quoted.join("\n"), styles)));
+        } else if let Some((start, _)) = list_item(line) {
+            let mut items = Vec::<String>::new();
+            while index < lines.len() {
+                let line = lines[index].trim();
+                match list_item(line) {
+                    Some((number, item)) if number.is_some() == start.is_some() => items.push(item.to_string()),
+                    // lines that start nothing else continue the item above
+                    None if !line.is_empty() && !starts_block(line) => {
+                        let last = items.last_mut().unwrap();
+                        last.push(' ');
+                        last.push_str(line);
+                    }
+                    _ => break,
+                }
+                index += 1;
+            }
+            // numbered lists count on from their first number
+            let markers = (0..items.len()).map(|offset| match start {
+                Some(start) => format!("{}.", start + offset as u32),
+                None => "•".to_string(),
+            });
+            blocks.push(Block::List(markers.zip(&items)
+                .map(|(marker, item)| ListItem { marker, text: parse_inline(item, styles) })
+                .collect()));
+        } else {
+            let mut paragraph = vec![line];
+            index += 1;
+            while index < lines.len() {
+                let line = lines[index].trim();
+                if line.is_empty() || starts_block(line) {
+                    break;
+                }
+                paragraph.push(line);
+                index += 1;
+            }
+            blocks.push(Block::Paragraph(parse_inline(&paragraph.join(" "), styles)));
+        }
+    }
+
+    blocks
+}
+
+/// Turns `code`, **strong** and *emphasized* text into spans, and drops the backslash of
+/// escaped punctuation. Markup that is never closed stays as it was written.
+pub fn parse_inline(text: &str, styles: &MarkdownStyles) -> RichText {
+    let mut rich_text = RichText::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '\\' {
+            if let Some(escaped) = rest[1..].chars().next().filter(|c| c.is_ascii_punctuation()) {
+                plain.push(escaped);
+                rest = &rest[1 + escaped.len_utf8()..];
+                continue;
+            }
+        }
+
+        let markup = match c {
+            '`' => Some(("`", styles.code)),
+            '*' | '_' if rest[1..].starts_with(c) => Some((&rest[..2], styles.strong)),
+            // underscores inside words, like snake_case, are not emphasis
+            '_' if plain.chars().next_back().is_some_and(char::is_alphanumeric) => None,
+            '*' | '_' => Some((&rest[..1], styles.emphasis)),
+            _ => None,
+        };
+
+        if let Some((delimiter, style)) = markup {
+            let inner = &rest[delimiter.len()..];
+            let closed = inner.find(delimiter)
+                .filter(|&end| end > 0 && (c == '`' || !inner.starts_with(char::is_whitespace)));
+            if let Some(end) = closed {
+                rich_text = rich_text.plain(&plain).span(&inner[..end], style);
+                plain.clear();
+                rest = &inner[end + delimiter.len()..];
+                continue;
+            }
+        }
+
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    rich_text.plain(&plain)
+}
+
+/// The fence a code block opens with, and the language named after it.
+fn code_fence(line: &str) -> Option<(&'static str, Option<String>)> {
+    let fence = ["```", "~~~"].into_iter().find(|fence| line.starts_with(fence))?;
+    let language = line.trim_start_matches(&fence[..1]).trim();
+    Some((fence, (!language.is_empty()).then(|| language.to_string())))
+}
+
+/// Three or more of the same `-`, `*` or `_`, spaces between them allowed.
+fn is_rule(line: &str) -> bool {
+    let marks = line.chars().filter(|c| !c.is_whitespace()).collect::<Vec<_>>();
+    marks.len() >= 3 && matches!(marks[0], '-' | '*' | '_') && marks.iter().all(|&c| c == marks[0])
+}
+
+fn heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    // closing hashes are only decoration
+    let text = rest.trim();
+    let text = match text.trim_end_matches('#') {
+        stripped if stripped.is_empty() || stripped.ends_with(' ') => stripped.trim_end(),
+        _ => text,
+    };
+    Some((level as u8, text))
+}
+
+/// The number of a numbered item, or none for a bullet, and the text of the item.
+fn list_item(line: &str) -> Option<(Option<u32>, &str)> {
+    if let Some(item) = ["- ", "* ", "+ "].into_iter().find_map(|bullet| line.strip_prefix(bullet)) {
+        return Some((None, item.trim_start()));
+    }
+
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let rest = &line[digits..];
+    let item = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") "))?;
+    match (1..=9).contains(&digits) {
+        true => Some((line[..digits].parse().ok(), item.trim_start())),
+        false => None,
+    }
+}
+
+/// Whether `line` begins a block of its own, ending the paragraph or list item before it.
+fn starts_block(line: &str) -> bool {
+    code_fence(line).is_some() || is_rule(line) || heading(line).is_some() || line.starts_with('>') || list_item(line).is_some()
+}
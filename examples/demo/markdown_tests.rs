@@ -0,0 +1,92 @@
+use clay_rs_wgpu::ui::ui_rich_text::SpanStyle;
+
+use crate::markdown::{parse, parse_inline, Block, MarkdownStyles};
+
+fn styles() -> MarkdownStyles {
+    MarkdownStyles {
+        code: SpanStyle::new().font(2),
+        strong: SpanStyle::new().font(1),
+        emphasis: SpanStyle::new().font(4),
+    }
+}
+
+/// The text of every block, with the kind of block in front of it.
+fn outline(blocks: &[Block]) -> Vec<String> {
+    blocks.iter()
+        .flat_map(|block| match block {
+            Block::Heading { level, text } => vec![format!("h{} {}", level, text.text())],
+            Block::Paragraph(text) => vec![format!("p {}", text.text())],
+            Block::List(items) => items.iter().map(|item| format!("li {} {}", item.marker, item.text.text())).collect(),
+            Block::Quote(blocks) => outline(blocks).into_iter().map(|line| format!("> {}", line)).collect(),
+            Block::Code { language, code } => vec![format!("code {} {}", language.as_deref().unwrap_or("-"), code)],
+            Block::Rule => vec!["hr".to_string()],
+        })
+        .collect()
+}
+
+#[test]
+fn parses_the_blocks_of_a_document() {
+    let text = "# Squirrels #\n\
+        They climb\ntrees.\n\
+        \n\
+        ## Food\n\
+        - nuts\n\
+        - seeds and\n  fungi\n\
+        \n\
+        3. bury\n\
+        4) forget\n\
+        \n\
+        > Squirrels are\n\
+        > # mighty\n\
+        \n\
+        ```rust\n    let acorns = 3;\n\n```\n\
+        ***\n\
+        #hashtag";
+
+    assert_eq!(outline(&parse(text, &styles())), [
+        "h1 Squirrels",
+        "p They climb trees.",
+        "h2 Food",
+        "li • nuts",
+        "li • seeds and fungi",
+        "li 3. bury",
+        "li 4. forget",
+        "> p Squirrels are",
+        "> h1 mighty",
+        "code rust     let acorns = 3;\n",
+        "hr",
+        "p #hashtag",
+    ]);
+}
+
+#[test]
+fn unclosed_code_blocks_run_to_the_end() {
+    let blocks = parse("~~~\n# not a heading\n- nor a list", &styles());
+
+    assert_eq!(outline(&blocks), ["code - # not a heading\n- nor a list"]);
+}
+
+#[test]
+fn inline_markup_becomes_spans() {
+    let styles = styles();
+    let text = parse_inline("Call `measure_text` with **care**, *quickly* and snake_case_names", &styles);
+
+    assert_eq!(text.text(), "Call measure_text with care, quickly and snake_case_names");
+
+    let spans = text.spans().iter()
+        .map(|span| (&text.text()[span.range.clone()], span.style))
+        .collect::<Vec<_>>();
+    assert_eq!(spans, [
+        ("measure_text", styles.code),
+        ("care", styles.strong),
+        ("quickly", styles.emphasis),
+    ]);
+}
+
+#[test]
+fn unclosed_markup_and_escapes_stay_text() {
+    let text = parse_inline("2 * 3 = 6, `open and \\*not emphasis\\*", &styles());
+
+    assert_eq!(text.text(), "2 * 3 = 6, `open and *not emphasis*");
+    assert!(text.spans().is_empty());
+}
@@ -0,0 +1,36 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// An empty directory of its own for a test, removed with everything in it when dropped.
+pub struct ScratchDirectory {
+    path: PathBuf,
+}
+
+impl ScratchDirectory {
+    /// `name` keeps the directories of tests running at the same time apart.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("clay-rs-wgpu-demo-{}-{}", name, std::process::id()));
+        // left behind by a run that didn't get to clean up
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Deref for ScratchDirectory {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ScratchDirectory {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
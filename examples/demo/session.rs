@@ -0,0 +1,167 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ui_layout::ClayState;
+
+/// Where the window was and how large it was, in physical pixels.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    /// some platforms, like Wayland, don't tell windows where they are
+    pub position: Option<(i32, i32)>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The smallest window a session restores, in physical pixels.
+const MIN_WINDOW_SIZE: (u32, u32) = (320, 240);
+
+/// How much of a restored window has to be on a monitor for its position to be kept.
+const MIN_VISIBLE: i64 = 32;
+
+/// Where Windows reports minimized windows to be.
+const MINIMIZED_POSITION: i32 = -32000;
+
+impl WindowGeometry {
+    /// Whether the geometry is worth saving, which it isn't for minimized or zero sized windows.
+    pub fn is_usable(&self) -> bool {
+        self.width > 0
+            && self.height > 0
+            && self.position.is_none_or(|(x, y)| x > MINIMIZED_POSITION && y > MINIMIZED_POSITION)
+    }
+
+    /// The geometry to open the window with, at least [`MIN_WINDOW_SIZE`] large, and without
+    /// a position when the window would not be on any of the `monitors`, given as x, y, width
+    /// and height.
+    pub fn restored(&self, monitors: &[(i32, i32, u32, u32)]) -> Self {
+        let width = self.width.max(MIN_WINDOW_SIZE.0);
+        let height = self.height.max(MIN_WINDOW_SIZE.1);
+
+        let position = self.position.filter(|&(x, y)| {
+            monitors.iter().any(|&(monitor_x, monitor_y, monitor_width, monitor_height)| {
+                let overlap = |start: i32, length: u32, monitor_start: i32, monitor_length: u32| {
+                    let end = (start as i64 + length as i64).min(monitor_start as i64 + monitor_length as i64);
+                    end - (start as i64).max(monitor_start as i64)
+                };
+                overlap(x, width, monitor_x, monitor_width) >= MIN_VISIBLE
+                    && overlap(y, height, monitor_y, monitor_height) >= MIN_VISIBLE
+            })
+        });
+
+        Self { position, width, height }
+    }
+}
+
+/// An open document, and where it was scrolled to.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionDocument {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub scroll_offset: (f32, f32),
+}
+
+/// What the demo restores on the next start: the documents opened from disk, which one was
+/// selected and where the window was.
+///
+/// Fields missing from the file keep their defaults, so sessions written by older versions
+/// still load.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub documents: Vec<SessionDocument>,
+    /// index into `documents`
+    pub selected_document: usize,
+    pub window: Option<WindowGeometry>,
+}
+
+impl Session {
+    /// The session file in the user's config directory, if the platform has one.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|directory| directory.join("clay-rs-wgpu-demo").join("session.json"))
+    }
+
+    /// Reads the session at `path`. A missing file is a fresh start, a file that can't be read
+    /// or parsed is logged and ignored.
+    pub fn load(path: &Path) -> Self {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Self::default(),
+            Err(error) => {
+                log::warn!("could not read the session {}: {}", path.display(), error);
+                return Self::default();
+            }
+        };
+
+        serde_json::from_str(&text).unwrap_or_else(|error| {
+            log::warn!("ignoring the corrupt session {}: {}", path.display(), error);
+            Self::default()
+        })
+    }
+
+    /// Writes the session to `path`, through a temporary file so a crash never leaves half of it.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+
+        let text = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        let temporary = path.with_extension("json.tmp");
+        std::fs::write(&temporary, text)?;
+        std::fs::rename(&temporary, path)
+    }
+
+    /// The session of the documents in `user_data`. Documents that weren't opened from disk
+    /// are left out.
+    pub fn capture(user_data: &ClayState, window: Option<WindowGeometry>) -> Self {
+        let mut selected_document = 0;
+        let mut documents = Vec::new();
+
+        for (index, document) in user_data.documents.iter().enumerate() {
+            let Some(path) = &document.path else {
+                continue;
+            };
+            if index == user_data.selected_document_index {
+                selected_document = documents.len();
+            }
+            documents.push(SessionDocument {
+                path: path.clone(),
+                scroll_offset: document.scroll_offset,
+            });
+        }
+
+        Self {
+            documents,
+            selected_document,
+            window,
+        }
+    }
+
+    /// Opens the documents of the session in place of those in `user_data`. Documents that
+    /// can no longer be read are skipped, and nothing changes when none of them can.
+    pub fn restore(&self, user_data: &mut ClayState) {
+        let paths = self.documents.iter().map(|document| document.path.clone()).collect::<Vec<_>>();
+        let mut restored = ClayState::default();
+        restored.open_paths(&paths);
+        if restored.documents.is_empty() {
+            return;
+        }
+
+        for document in &mut restored.documents {
+            let saved = self.documents.iter()
+                .find(|saved| Some(&saved.path) == document.path.as_ref());
+            if let Some(saved) = saved {
+                document.scroll_offset = saved.scroll_offset;
+            }
+        }
+
+        let selected = self.documents.get(self.selected_document)
+            .and_then(|selected| restored.documents.iter().position(|document| document.path.as_ref() == Some(&selected.path)))
+            .unwrap_or(0);
+
+        user_data.finish_rename(false);
+        user_data.documents = restored.documents;
+        user_data.selected_document_index = selected;
+        user_data.shown_document_index = None;
+    }
+}
@@ -0,0 +1,100 @@
+use crate::scratch_directory::ScratchDirectory;
+use crate::session::{Session, SessionDocument, WindowGeometry};
+use crate::ui_layout::{initialize_user_data, ClayState};
+
+#[test]
+fn saved_sessions_load_back() {
+    let directory = ScratchDirectory::new("round_trip");
+    let path = directory.join("config").join("session.json");
+    let session = Session {
+        documents: vec![SessionDocument { path: directory.join("squirrels.md"), scroll_offset: (0.0, -120.0) }],
+        selected_document: 0,
+        window: Some(WindowGeometry { position: None, width: 1024, height: 768 }),
+    };
+
+    session.save(&path).unwrap();
+
+    assert_eq!(Session::load(&path), session);
+}
+
+#[test]
+fn missing_and_corrupt_sessions_start_fresh() {
+    let directory = ScratchDirectory::new("corrupt");
+    let path = directory.join("session.json");
+
+    assert_eq!(Session::load(&path), Session::default());
+
+    std::fs::write(&path, "{ \"documents\": [").unwrap();
+    assert_eq!(Session::load(&path), Session::default());
+
+    std::fs::write(&path, "{ \"selected_document\": 2 }").unwrap();
+    assert_eq!(Session::load(&path).selected_document, 2);
+}
+
+#[test]
+fn restoring_skips_documents_that_are_gone() {
+    let directory = ScratchDirectory::new("restore");
+    std::fs::write(directory.join("squirrels.md"), "# Squirrels").unwrap();
+    std::fs::write(directory.join("acorns.txt"), "Acorns").unwrap();
+
+    let mut user_data = ClayState::default();
+    user_data.open_paths(&[directory.join("squirrels.md"), directory.join("acorns.txt")]);
+    user_data.new_document();
+    user_data.documents[1].scroll_offset = (0.0, -80.0);
+    user_data.selected_document_index = 1;
+
+    let session = Session::capture(&user_data, None);
+    assert_eq!(session.documents.len(), 2);
+    assert_eq!(session.selected_document, 1);
+
+    std::fs::remove_file(directory.join("squirrels.md")).unwrap();
+    let mut restored = ClayState::default();
+    initialize_user_data(&mut restored);
+    session.restore(&mut restored);
+
+    assert_eq!(restored.documents.len(), 1);
+    assert_eq!(restored.documents[0].title, "acorns");
+    assert_eq!(restored.documents[0].scroll_offset, (0.0, -80.0));
+    assert_eq!(restored.selected_document_index, 0);
+}
+
+#[test]
+fn an_empty_session_keeps_the_sample_documents() {
+    let mut user_data = ClayState::default();
+    initialize_user_data(&mut user_data);
+    let samples = user_data.documents.len();
+
+    Session::default().restore(&mut user_data);
+
+    assert_eq!(user_data.documents.len(), samples);
+}
+
+#[test]
+fn minimized_and_empty_windows_are_not_worth_saving() {
+    let window = WindowGeometry { position: Some((100, 50)), width: 1024, height: 768 };
+
+    assert!(window.is_usable());
+    assert!(WindowGeometry { position: None, ..window }.is_usable());
+    assert!(!WindowGeometry { position: Some((-32000, -32000)), ..window }.is_usable());
+    assert!(!WindowGeometry { width: 0, ..window }.is_usable());
+    assert!(!WindowGeometry { height: 0, ..window }.is_usable());
+}
+
+#[test]
+fn restored_windows_are_large_enough_and_on_a_monitor() {
+    let monitors = [(0, 0, 1920, 1080), (1920, 0, 1280, 1024)];
+
+    let window = WindowGeometry { position: Some((2000, 100)), width: 1024, height: 768 };
+    assert_eq!(window.restored(&monitors), window);
+    // maximized windows reach a little past their monitor
+    let maximized = WindowGeometry { position: Some((-8, -8)), width: 1936, height: 1096 };
+    assert_eq!(maximized.restored(&monitors), maximized);
+
+    let tiny = WindowGeometry { position: Some((100, 100)), width: 10, height: 0 };
+    assert_eq!(tiny.restored(&monitors), WindowGeometry { position: Some((100, 100)), width: 320, height: 240 });
+
+    // a monitor that was unplugged
+    let elsewhere = WindowGeometry { position: Some((3300, 100)), width: 800, height: 600 };
+    assert_eq!(elsewhere.restored(&monitors), WindowGeometry { position: None, ..elsewhere });
+    assert_eq!(window.restored(&[]).position, None);
+}
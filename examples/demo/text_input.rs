@@ -0,0 +1,216 @@
+use std::ops::Range;
+use std::time::Instant;
+
+use winit::event::KeyEvent;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use clay_rs_wgpu::ui::ui_clipboard::Clipboard;
+
+/// How long the caret stays on, and then off, in milliseconds.
+const CARET_BLINK_MS: u128 = 530;
+
+/// What a key did to the input beyond editing it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextInputEvent {
+    None,
+    Submit,
+    Cancel,
+}
+
+/// Text being composed by an input method, shown at the caret until it is committed.
+pub struct Preedit {
+    pub text: String,
+    /// the byte range the input method marks inside the text
+    pub cursor: Option<(usize, usize)>,
+}
+
+/// The text, caret and selection of a single line text field.
+///
+/// The caret and the selection anchor are byte offsets, always on a char boundary.
+/// Nothing is selected while they are the same.
+pub struct TextInput {
+    text: String,
+    cursor: usize,
+    anchor: usize,
+    preedit: Option<Preedit>,
+    blink_start: Instant,
+}
+
+impl TextInput {
+    /// An input holding `text`, with the caret at its end.
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            cursor: text.len(),
+            anchor: text.len(),
+            preedit: None,
+            blink_start: Instant::now(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn preedit(&self) -> Option<&Preedit> {
+        self.preedit.as_ref()
+    }
+
+    pub fn selection(&self) -> Range<usize> {
+        self.cursor.min(self.anchor)..self.cursor.max(self.anchor)
+    }
+
+    pub fn selected_text(&self) -> &str {
+        &self.text[self.selection()]
+    }
+
+    /// The caret blinks, but stays on for a moment after every edit or move.
+    pub fn caret_visible(&self) -> bool {
+        (self.blink_start.elapsed().as_millis() / CARET_BLINK_MS) % 2 == 0
+    }
+
+    /// Replaces the selection with `text`, leaving the caret after it.
+    pub fn insert(&mut self, text: &str) {
+        self.delete_selection();
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+        self.anchor = self.cursor;
+        self.blink_start = Instant::now();
+    }
+
+    /// Deletes the selection, or the character before the caret.
+    pub fn backspace(&mut self) {
+        if self.selection().is_empty() {
+            self.anchor = self.previous_boundary(self.cursor);
+        }
+        self.delete_selection();
+    }
+
+    /// Deletes the selection, or the character after the caret.
+    pub fn delete(&mut self) {
+        if self.selection().is_empty() {
+            self.anchor = self.next_boundary(self.cursor);
+        }
+        self.delete_selection();
+    }
+
+    pub fn delete_selection(&mut self) {
+        let selection = self.selection();
+        self.text.replace_range(selection.clone(), "");
+        self.cursor = selection.start;
+        self.anchor = selection.start;
+        self.blink_start = Instant::now();
+    }
+
+    /// Moves the caret to `offset`, growing the selection when `extend` is set.
+    pub fn move_to(&mut self, offset: usize, extend: bool) {
+        self.cursor = offset.min(self.text.len());
+        if !extend {
+            self.anchor = self.cursor;
+        }
+        self.blink_start = Instant::now();
+    }
+
+    /// Moves one character left, or to the start of the selection when it is dropped.
+    pub fn move_left(&mut self, extend: bool) {
+        match self.selection().is_empty() || extend {
+            true => self.move_to(self.previous_boundary(self.cursor), extend),
+            false => self.move_to(self.selection().start, false),
+        }
+    }
+
+    /// Moves one character right, or to the end of the selection when it is dropped.
+    pub fn move_right(&mut self, extend: bool) {
+        match self.selection().is_empty() || extend {
+            true => self.move_to(self.next_boundary(self.cursor), extend),
+            false => self.move_to(self.selection().end, false),
+        }
+    }
+
+    pub fn home(&mut self, extend: bool) {
+        self.move_to(0, extend);
+    }
+
+    pub fn end(&mut self, extend: bool) {
+        self.move_to(self.text.len(), extend);
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = 0;
+        self.cursor = self.text.len();
+        self.blink_start = Instant::now();
+    }
+
+    /// Shows text the input method is composing at the caret, empty text ends the composition.
+    pub fn set_preedit(&mut self, text: String, cursor: Option<(usize, usize)>) {
+        self.preedit = match text.is_empty() {
+            true => None,
+            false => Some(Preedit { text, cursor }),
+        };
+        self.blink_start = Instant::now();
+    }
+
+    /// Edits the input for a key press, with copy, cut and paste going through `clipboard`.
+    pub fn handle_key(&mut self, event: &KeyEvent, modifiers: ModifiersState, clipboard: Option<&mut dyn Clipboard>) -> TextInputEvent {
+        if !event.state.is_pressed() {
+            return TextInputEvent::None;
+        }
+
+        let shortcut = modifiers.control_key() || modifiers.super_key();
+        let extend = modifiers.shift_key();
+
+        match &event.logical_key {
+            Key::Named(NamedKey::Enter) => return TextInputEvent::Submit,
+            Key::Named(NamedKey::Escape) => return TextInputEvent::Cancel,
+            Key::Named(NamedKey::Backspace) => self.backspace(),
+            Key::Named(NamedKey::Delete) => self.delete(),
+            Key::Named(NamedKey::ArrowLeft) => self.move_left(extend),
+            Key::Named(NamedKey::ArrowRight) => self.move_right(extend),
+            Key::Named(NamedKey::Home) => self.home(extend),
+            Key::Named(NamedKey::End) => self.end(extend),
+            Key::Character(character) if shortcut => match character.to_lowercase().as_str() {
+                "a" => self.select_all(),
+                "c" | "x" => {
+                    if let Some(clipboard) = clipboard {
+                        if !self.selection().is_empty() {
+                            clipboard.set_text(self.selected_text());
+                        }
+                    }
+                    if character.eq_ignore_ascii_case("x") {
+                        self.delete_selection();
+                    }
+                }
+                "v" => {
+                    if let Some(text) = clipboard.and_then(|clipboard| clipboard.get_text()) {
+                        // the input is a single line
+                        self.insert(&text.replace(['\r', '\n'], " "));
+                    }
+                }
+                _ => {}
+            },
+            _ => {
+                // while an input method is composing, its text arrives through the ime events
+                if let Some(text) = event.text.as_ref().filter(|_| !shortcut && self.preedit.is_none()) {
+                    let text = text.chars().filter(|c| !c.is_control()).collect::<String>();
+                    if !text.is_empty() {
+                        self.insert(&text);
+                    }
+                }
+            }
+        }
+
+        TextInputEvent::None
+    }
+
+    fn previous_boundary(&self, offset: usize) -> usize {
+        self.text[..offset].char_indices().next_back().map_or(0, |(index, _)| index)
+    }
+
+    fn next_boundary(&self, offset: usize) -> usize {
+        self.text[offset..].chars().next().map_or(offset, |c| offset + c.len_utf8())
+    }
+}
@@ -0,0 +1,61 @@
+use crate::text_input::TextInput;
+
+#[test]
+fn typing_replaces_the_selection() {
+    let mut input = TextInput::new("Squirrels");
+    input.select_all();
+
+    input.insert("Acorns");
+
+    assert_eq!(input.text(), "Acorns");
+    assert_eq!(input.cursor(), 6);
+    assert!(input.selection().is_empty());
+}
+
+#[test]
+fn backspace_and_delete_remove_whole_characters() {
+    let mut input = TextInput::new("naïve");
+    input.move_to(4, false);
+
+    input.backspace();
+    assert_eq!(input.text(), "nave");
+
+    input.delete();
+    assert_eq!(input.text(), "nae");
+    assert_eq!(input.cursor(), 2);
+}
+
+#[test]
+fn shift_arrows_extend_the_selection() {
+    let mut input = TextInput::new("Squirrels");
+    input.home(false);
+
+    input.move_right(true);
+    input.move_right(true);
+    assert_eq!(input.selected_text(), "Sq");
+
+    input.end(true);
+    assert_eq!(input.selected_text(), "Squirrels");
+}
+
+#[test]
+fn arrows_without_shift_collapse_the_selection() {
+    let mut input = TextInput::new("Squirrels");
+    input.select_all();
+
+    input.move_left(false);
+
+    assert_eq!(input.cursor(), 0);
+    assert!(input.selection().is_empty());
+}
+
+#[test]
+fn an_empty_preedit_ends_the_composition() {
+    let mut input = TextInput::new("");
+
+    input.set_preedit("か".to_string(), Some((0, 3)));
+    assert!(input.preedit().is_some());
+
+    input.set_preedit(String::new(), None);
+    assert!(input.preedit().is_none());
+}
@@ -0,0 +1,989 @@
+use clay_layout::{
+        elements::FloatingAttachToElement, fixed, grow, layout::{
+        Alignment, 
+        LayoutDirection::TopToBottom, 
+        Padding,
+    }, percent, render_commands::RenderCommand, text::{TextConfig, TextElementConfigWrapMode}, Clay, Color, Declaration
+};
+
+use clay_rs_wgpu::ui::ui_focus::UIFocus;
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use clay_rs_wgpu::ui::ui_renderer::UIState;
+use clay_rs_wgpu::ui::ui_rich_text::{RichText, SpanStyle};
+
+use crate::commands::{Accelerator, Command, CommandRegistry};
+use crate::file_browser::{FileBrowser, FileEntry};
+use crate::markdown::{self, Block, MarkdownStyles};
+use crate::text_input::TextInput;
+
+const CLAY_ALIGN_Y_CENTER: Alignment = Alignment{ x: clay_layout::layout::LayoutAlignmentX::Left, y: clay_layout::layout::LayoutAlignmentY::Center};
+
+const WHITE: Color = Color::rgb(255.0, 255.0, 255.0);
+
+pub const FONT_ID_TITLE: u16 = 1;
+pub const FONT_ID_MONOSPACE: u16 = 2;
+/// underlined, for text an input method is still composing
+pub const FONT_ID_PREEDIT: u16 = 3;
+pub const FONT_ID_ITALIC: u16 = 4;
+
+const BODY_FONT_SIZE: u16 = 20;
+const CODE_FONT_SIZE: u16 = 18;
+
+pub const COMMAND_NEW_DOCUMENT: &str = "new_document";
+pub const COMMAND_OPEN_DOCUMENT: &str = "open_document";
+pub const COMMAND_CLOSE_DOCUMENT: &str = "close_document";
+pub const COMMAND_RENAME_DOCUMENT: &str = "rename_document";
+
+/// The commands of the file menu, from top to bottom.
+const FILE_MENU: [&str; 3] = [COMMAND_NEW_DOCUMENT, COMMAND_OPEN_DOCUMENT, COMMAND_CLOSE_DOCUMENT];
+
+trait CustomStyles {
+    fn layout_expand(&mut self) -> Self;
+    fn content_background_config(&mut self) -> Self;
+}
+
+impl CustomStyles for Declaration{
+    fn layout_expand(&mut self) -> Self {
+        *self.layout()
+            .width(grow!())
+            .height(grow!())
+            .end()
+    }
+
+    fn content_background_config(&mut self) -> Self {
+        *self.background_color(Color::rgb(90.0, 90.0, 90.0))
+            .corner_radius()
+                .all(8.0)
+                .end()
+    }
+}
+
+/// The number clay gives the element `label`, which its render commands carry as their id.
+fn element_id(clay: &Clay, label: &str) -> u32 {
+    clay.id(label).id.id
+}
+
+/// Like [`element_id`], for one of many elements sharing `label`.
+fn element_id_index(clay: &Clay, label: &str, index: u32) -> u32 {
+    clay.id_index(label, index).id.id
+}
+
+/// Returns the command of the button when it was clicked or activated from the keyboard.
+/// Buttons without a command don't do anything yet, so they don't take focus either.
+fn render_header_button(clay: &Clay, focus: &mut UIFocus, text: &str, command: Option<&'static str>, mouse_down_rising_edge: bool) -> Option<&'static str> {
+    let activated = command.is_some() && focus.register(element_id(clay, text));
+    let clicked = mouse_down_rising_edge && clay.pointer_over(clay.id(text));
+
+    clay.with(&Declaration::new()
+        .id(clay.id(text))
+        .layout()
+            .padding(Padding::new(16,16,8,8))
+            .end()
+        .background_color(Color::rgb(140.0, 140.0, 140.0))
+        .corner_radius()
+            .all(5.0)
+            .end()
+        ,|_|{
+            clay.text(
+                text, 
+                TextConfig::new()
+                    .font_size(16)
+                    .color(WHITE)
+                    .end()
+            );
+        }
+    );
+
+    command.filter(|_| activated || clicked)
+}
+
+/// Draws a menu item for `command`, with its accelerator on the right. Returns whether it
+/// was clicked or activated from the keyboard.
+fn render_dropdown_menu_item(clay: &Clay, focus: &mut UIFocus, command: &Command<ClayState>, mouse_down_rising_edge: bool) -> bool {
+    let id = element_id(clay, command.id);
+    let activated = focus.register(id);
+    // only highlighted items have a background, for the focus ring to go around
+    let highlighted = focus.is_focused(id) || clay.pointer_over(clay.id(command.id));
+
+    let mut item = Declaration::new();
+    item.id(clay.id(command.id))
+        .layout()
+            .width(grow!())
+            .padding(Padding::all(16))
+            .child_gap(16)
+            .end();
+    if highlighted {
+        item.background_color(Color::rgb(70.0, 70.0, 70.0))
+            .corner_radius()
+                .all(8.0)
+                .end();
+    }
+
+    clay.with(&item,
+        |_|{
+            clay.text(
+                command.label, 
+                TextConfig::new()
+                    .font_size(16)
+                    .color(WHITE)
+                    .end()
+            );
+            if let Some(accelerator_label) = &command.accelerator_label {
+                clay.with(&Declaration::new()
+                    .layout()
+                        .width(grow!())
+                        .end()
+                , |_| {});
+                clay.text(
+                    accelerator_label,
+                    TextConfig::new()
+                        .font_size(16)
+                        .color(Color::rgb(170.0, 170.0, 170.0))
+                        .end()
+                );
+            }
+        }
+    );
+
+    activated || (mouse_down_rising_edge && clay.pointer_over(clay.id(command.id)))
+}
+
+/// Draws `input` as a single line text field, with its selection, caret and the text an
+/// input method is composing.
+fn render_text_input(clay: &Clay, id: &str, input: &TextInput) {
+    let text_config = |font_id: u16| TextConfig::new()
+        .font_id(font_id)
+        .font_size(20)
+        .color(WHITE)
+        .wrap_mode(TextElementConfigWrapMode::None)
+        .end();
+
+    let caret = |visible: bool| clay.with(&Declaration::new()
+        .layout()
+            .width(fixed!(2.0))
+            .height(fixed!(24.0))
+            .end()
+        .background_color(match visible {
+            true => WHITE,
+            false => Color::rgba(0.0, 0.0, 0.0, 0.0),
+        }),
+        |_| {}
+    );
+
+    clay.with(&Declaration::new()
+        .id(clay.id(id))
+        .layout()
+            .width(grow!())
+            .padding(Padding::new(4, 4, 2, 2))
+            .child_alignment(CLAY_ALIGN_Y_CENTER)
+            .end()
+        .background_color(Color::rgb(60.0, 60.0, 60.0))
+        .corner_radius()
+            .all(4.0)
+            .end(),
+        |_| {
+            let text = input.text();
+            let cursor = input.cursor();
+
+            if let Some(preedit) = input.preedit() {
+                clay.text(&text[..cursor], text_config(0));
+                clay.text(&preedit.text, text_config(FONT_ID_PREEDIT));
+                caret(true);
+                clay.text(&text[cursor..], text_config(0));
+                return;
+            }
+
+            let selection = input.selection();
+            clay.text(&text[..selection.start], text_config(0));
+            if cursor == selection.start {
+                caret(input.caret_visible());
+            }
+            if !selection.is_empty() {
+                clay.with(&Declaration::new()
+                    .background_color(Color::rgb(50.0, 100.0, 220.0)),
+                    |_| {
+                        clay.text(&text[selection.clone()], text_config(0));
+                    }
+                );
+            }
+            if cursor != selection.start {
+                caret(input.caret_visible());
+            }
+            clay.text(&text[selection.end..], text_config(0));
+        }
+    );
+}
+
+/// What the user picked in the file browser.
+pub enum BrowserAction {
+    Up,
+    Navigate(PathBuf),
+    Open(PathBuf),
+    Cancel,
+}
+
+/// A clickable row of the file browser, returning whether it was clicked or activated from
+/// the keyboard.
+fn render_browser_row(clay: &Clay, focus: &mut UIFocus, index: u32, text: &str, mouse_down_rising_edge: bool) -> bool {
+    let activated = focus.register(element_id_index(clay, "file_browser_row", index));
+    let mut clicked = false;
+
+    clay.with_styling(
+        Declaration::new()
+            .id(clay.id_index("file_browser_row", index))
+            .layout()
+                .width(grow!())
+                .padding(Padding::new(16, 16, 8, 8))
+                .end()
+            .background_color(Color::rgb(90.0, 90.0, 90.0))
+            .corner_radius()
+                .all(5.0)
+                .end(),
+        |styling| {
+            if clay.hovered() {
+                clicked = mouse_down_rising_edge;
+                styling.background_color(Color::rgb(120.0, 120.0, 120.0));
+            }
+        },
+        || {
+            clay.text(text, TextConfig::new()
+                .font_size(20)
+                .color(WHITE)
+                .end()
+            );
+        }
+    );
+
+    activated || clicked
+}
+
+/// Lists the directory being browsed, with a row to go up and one to cancel.
+fn render_file_browser(clay: &Clay, browser: &FileBrowser, focus: &mut UIFocus, mouse_down_rising_edge: bool) -> Option<BrowserAction> {
+    let mut action = None;
+
+    clay.text(browser.directory_label(), TextConfig::new()
+        .font_id(FONT_ID_TITLE)
+        .font_size(24)
+        .color(WHITE)
+        .end()
+    );
+
+    if render_browser_row(clay, focus, 0, "Cancel", mouse_down_rising_edge) {
+        action = Some(BrowserAction::Cancel);
+    }
+    if browser.directory().parent().is_some() && render_browser_row(clay, focus, 1, "..", mouse_down_rising_edge) {
+        action = Some(BrowserAction::Up);
+    }
+
+    if let Some(error) = browser.error() {
+        clay.text(error, TextConfig::new()
+            .font_size(20)
+            .color(Color::rgb(255.0, 160.0, 160.0))
+            .end()
+        );
+    }
+
+    for (index, entry) in browser.entries().iter().enumerate() {
+        let FileEntry { label, path, is_directory, .. } = entry;
+        if render_browser_row(clay, focus, index as u32 + 2, label, mouse_down_rising_edge) {
+            action = Some(match is_directory {
+                true => BrowserAction::Navigate(path.clone()),
+                false => BrowserAction::Open(path.clone()),
+            });
+        }
+    }
+
+    action
+}
+
+/// How inline markdown looks in documents.
+pub fn markdown_styles() -> MarkdownStyles {
+    MarkdownStyles {
+        // on the background of code blocks
+        code: SpanStyle::new().font(FONT_ID_MONOSPACE).color(glyphon::Color::rgb(230, 180, 90)).background(glyphon::Color::rgb(50, 50, 56)),
+        strong: SpanStyle::new().font(FONT_ID_TITLE),
+        emphasis: SpanStyle::new().font(FONT_ID_ITALIC),
+    }
+}
+
+fn heading_font_size(level: u8) -> u16 {
+    match level {
+        1 => 36,
+        2 => 30,
+        3 => 26,
+        _ => 22,
+    }
+}
+
+/// Declares the spans of `text` with the renderer, then `text` as a single text element.
+fn render_rich_text(clay: &Clay, ui_state: Option<&Rc<RefCell<UIState>>>, text: &RichText, font_id: u16, font_size: u16, color: Color) {
+    if let Some(ui_state) = ui_state {
+        ui_state.borrow_mut().ui_text.rich_text.declare(text);
+    }
+
+    clay.text(text.text(), TextConfig::new()
+        .font_id(font_id)
+        .font_size(font_size)
+        .color(color)
+        .end()
+    );
+}
+
+/// Lays out markdown blocks from top to bottom, in the element they are declared in.
+fn render_markdown(clay: &Clay, ui_state: Option<&Rc<RefCell<UIState>>>, blocks: &[Block], color: Color) {
+    for block in blocks {
+        match block {
+            Block::Heading { level, text } => {
+                render_rich_text(clay, ui_state, text, FONT_ID_TITLE, heading_font_size(*level), color);
+            }
+            Block::Paragraph(text) => {
+                render_rich_text(clay, ui_state, text, 0, BODY_FONT_SIZE, color);
+            }
+            Block::List(items) => {
+                for item in items {
+                    clay.with(&Declaration::new()
+                        .layout()
+                            .width(grow!())
+                            .padding(Padding::new(8, 0, 0, 0))
+                            .child_gap(8)
+                            .end(),
+                        |_| {
+                            clay.text(&item.marker, TextConfig::new()
+                                .font_size(BODY_FONT_SIZE)
+                                .color(color)
+                                .end()
+                            );
+                            clay.with(&Declaration::new()
+                                .layout()
+                                    .width(grow!())
+                                    .end(),
+                                |_| render_rich_text(clay, ui_state, &item.text, 0, BODY_FONT_SIZE, color)
+                            );
+                        }
+                    );
+                }
+            }
+            Block::Quote(blocks) => {
+                clay.with(&Declaration::new()
+                    .layout()
+                        .width(grow!())
+                        .child_gap(12)
+                        .end(),
+                    |_| {
+                        clay.with(&Declaration::new()
+                            .layout()
+                                .width(fixed!(4.0))
+                                .height(grow!())
+                                .end()
+                            .background_color(Color::rgb(140.0, 140.0, 140.0)),
+                            |_| {}
+                        );
+                        clay.with(&Declaration::new()
+                            .layout()
+                                .width(grow!())
+                                .direction(TopToBottom)
+                                .child_gap(8)
+                                .end(),
+                            |_| render_markdown(clay, ui_state, blocks, Color::rgb(210.0, 210.0, 210.0))
+                        );
+                    }
+                );
+            }
+            Block::Code { language, code } => {
+                clay.with(&Declaration::new()
+                    .layout()
+                        .width(grow!())
+                        .direction(TopToBottom)
+                        .padding(Padding::all(12))
+                        .child_gap(8)
+                        .end()
+                    .background_color(Color::rgb(50.0, 50.0, 56.0))
+                    .corner_radius()
+                        .all(6.0)
+                        .end(),
+                    |_| {
+                        if let Some(language) = language {
+                            clay.text(language, TextConfig::new()
+                                .font_size(14)
+                                .color(Color::rgb(170.0, 170.0, 170.0))
+                                .end()
+                            );
+                        }
+                        clay.text(code, TextConfig::new()
+                            .font_id(FONT_ID_MONOSPACE)
+                            .font_size(CODE_FONT_SIZE)
+                            .color(Color::rgb(230.0, 230.0, 230.0))
+                            .end()
+                        );
+                    }
+                );
+            }
+            Block::Rule => {
+                clay.with(&Declaration::new()
+                    .layout()
+                        .width(grow!())
+                        .height(fixed!(2.0))
+                        .end()
+                    .background_color(Color::rgb(120.0, 120.0, 120.0)),
+                    |_| {}
+                );
+            }
+        }
+    }
+}
+
+pub struct Document {
+    pub title: String,
+    /// the contents, parsed as markdown
+    pub blocks: Vec<Block>,
+    /// where the document was loaded from, the samples have none
+    pub path: Option<PathBuf>,
+    /// where main_content was scrolled to when the document was last shown
+    pub scroll_offset: (f32, f32),
+}
+
+impl Document {
+    /// Loads a text file, titled after its file name.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let title = path.file_stem()
+            .map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().into_owned());
+
+        Ok(Self {
+            title,
+            blocks: markdown::parse(&contents, &markdown_styles()),
+            path: Some(path.to_path_buf()),
+            scroll_offset: (0.0, 0.0),
+        })
+    }
+}
+
+/// A document title being edited in the sidebar.
+pub struct Rename {
+    pub document_index: usize,
+    pub input: TextInput,
+}
+
+#[derive(Default)]
+pub struct ClayState{
+    pub documents: Vec<Document>,
+    pub selected_document_index: usize,
+    pub mouse_down_rising_edge: bool,
+    pub mouse_position: (f32,f32),
+    pub scroll_delta: (f32,f32),
+    pub size:(f32,f32),
+    pub rename: Option<Rename>,
+    pub focus: UIFocus,
+    /// opened from the keyboard, the menu otherwise shows while the pointer is over it
+    pub file_menu_open: bool,
+    pub commands: CommandRegistry<ClayState>,
+    /// shown instead of the selected document while picking one to open
+    pub file_browser: Option<FileBrowser>,
+    /// picked in the file browser, run before the next layout because the frame being drawn
+    /// still borrows the text of the browser
+    pub browser_action: Option<BrowserAction>,
+    /// the document main_content was scrolled for in the last frame
+    pub shown_document_index: Option<usize>,
+    /// where rich text is declared before it is laid out, without it the spans are drawn plain
+    pub ui_state: Option<Rc<RefCell<UIState>>>,
+}
+
+impl ClayState {
+    /// Runs the command `id`, the same way for menus and keyboard shortcuts.
+    pub fn run_command(&mut self, id: &str) {
+        if let Some(action) = self.commands.action(id) {
+            action(self);
+        }
+    }
+
+    /// Runs what was picked in the file browser during the last layout.
+    pub fn run_browser_action(&mut self) {
+        match self.browser_action.take() {
+            Some(BrowserAction::Up) => {
+                if let Some(browser) = &mut self.file_browser {
+                    browser.navigate_up();
+                }
+            }
+            Some(BrowserAction::Navigate(directory)) => {
+                if let Some(browser) = &mut self.file_browser {
+                    browser.navigate(&directory);
+                }
+            }
+            Some(BrowserAction::Open(path)) => {
+                if let Err(error) = self.open_path(&path) {
+                    log::warn!("could not open {}: {}", path.display(), error);
+                }
+            }
+            Some(BrowserAction::Cancel) => self.file_browser = None,
+            None => {}
+        }
+    }
+
+    /// Adds an empty document and selects it.
+    pub fn new_document(&mut self) {
+        self.finish_rename(true);
+        self.file_browser = None;
+        self.documents.push(Document {
+            title: "Untitled".to_string(),
+            blocks: Vec::new(),
+            path: None,
+            scroll_offset: (0.0, 0.0),
+        });
+        self.selected_document_index = self.documents.len() - 1;
+    }
+
+    /// Shows the file browser, in the directory of the selected document if it has one.
+    pub fn open_document(&mut self) {
+        let directory = self.documents.get(self.selected_document_index)
+            .and_then(|document| document.path.as_deref())
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        self.file_browser = Some(FileBrowser::new(&directory));
+    }
+
+    /// Closes the selected document, selecting the one that takes its place.
+    pub fn close_document(&mut self) {
+        // the title being edited may belong to a document that is about to move
+        self.finish_rename(false);
+        if self.selected_document_index < self.documents.len() {
+            self.documents.remove(self.selected_document_index);
+        }
+        self.shown_document_index = None;
+        self.selected_document_index = self.selected_document_index.min(self.documents.len().saturating_sub(1));
+    }
+
+    /// Opens the document at `path` and selects it, or just selects it when it is already open.
+    pub fn open_path(&mut self, path: &Path) -> io::Result<()> {
+        let already_open = self.documents.iter()
+            .position(|document| document.path.as_deref().is_some_and(|open| same_file(open, path)));
+
+        self.selected_document_index = match already_open {
+            Some(index) => index,
+            None => {
+                self.documents.push(Document::load(path)?);
+                self.documents.len() - 1
+            }
+        };
+        self.file_browser = None;
+        Ok(())
+    }
+
+    /// Replaces the documents with the ones at `paths`, leaving out those that can't be read.
+    pub fn open_paths(&mut self, paths: &[PathBuf]) {
+        self.finish_rename(false);
+        self.documents.clear();
+        self.shown_document_index = None;
+        for path in paths {
+            if let Err(error) = self.open_path(path) {
+                log::warn!("could not open {}: {}", path.display(), error);
+            }
+        }
+        self.selected_document_index = 0;
+    }
+
+    /// Starts editing the title of the selected document.
+    pub fn rename_document(&mut self) {
+        if self.selected_document_index < self.documents.len() {
+            self.start_rename(self.selected_document_index);
+        }
+    }
+
+    /// Starts editing the title of a document, with all of it selected.
+    pub fn start_rename(&mut self, document_index: usize) {
+        let mut input = TextInput::new(&self.documents[document_index].title);
+        input.select_all();
+        self.rename = Some(Rename { document_index, input });
+    }
+
+    /// Stops editing the title, keeping the new one when `commit` is set and it isn't blank.
+    pub fn finish_rename(&mut self, commit: bool) {
+        let Some(rename) = self.rename.take() else {
+            return;
+        };
+
+        let title = rename.input.text().trim();
+        if commit && !title.is_empty() {
+            self.documents[rename.document_index].title = title.to_string();
+        }
+    }
+}
+
+/// Whether two paths lead to the same file, however they are written.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+pub fn initialize_user_data(user_data: &mut ClayState){
+    user_data.commands.register(COMMAND_NEW_DOCUMENT, "New", Some(Accelerator::shortcut("n")), ClayState::new_document);
+    user_data.commands.register(COMMAND_OPEN_DOCUMENT, "Open", Some(Accelerator::shortcut("o")), ClayState::open_document);
+    user_data.commands.register(COMMAND_CLOSE_DOCUMENT, "Close", Some(Accelerator::shortcut("w")), ClayState::close_document);
+    user_data.commands.register(COMMAND_RENAME_DOCUMENT, "Rename", None, ClayState::rename_document);
+
+    user_data.documents
+        .push(Document{
+            title:"Squirrels".to_string(), 
+            path: None,
+            scroll_offset: (0.0, 0.0),
+            blocks: markdown::parse("# The Secret Life of Squirrels: Nature's Clever Acrobats\n\nSquirrels are often overlooked creatures, dismissed as mere park inhabitants or backyard nuisances. Yet, beneath their fluffy tails and twitching noses lies an intricate world of cunning, agility, and survival tactics that are nothing short of fascinating. As one of the most common mammals in North America, squirrels have adapted to a wide range of environments from bustling urban centers to tranquil forests and have developed a variety of unique behaviors that continue to intrigue scientists and nature enthusiasts alike.\n\n## Master Tree Climbers\n\nAt the heart of a squirrel's skill set is its impressive ability to navigate trees with ease. Whether they're darting from branch to branch or leaping across wide gaps, squirrels possess an innate talent for acrobatics. Their powerful hind legs, which are longer than their front legs, give them remarkable jumping power. With a tail that acts as a counterbalance, squirrels can leap distances of up to ten times the length of their body, making them some of the best aerial acrobats in the animal kingdom.\n\nBut it's not just their agility that makes them exceptional climbers. Squirrels' sharp, curved claws allow them to grip tree bark with precision, while the soft pads on their feet provide traction on slippery surfaces. Their ability to run at high speeds and scale vertical trunks with ease is a testament to the evolutionary adaptations that have made them so successful in their arboreal habitats.\n\n## Food Hoarders Extraordinaire\n\nSquirrels are often seen frantically gathering nuts, seeds, and even fungi in preparation for winter. While this behavior may seem like instinctual hoarding, it is actually a survival strategy that has been honed over millions of years. Known as \"scatter hoarding,\" squirrels store their food in a variety of hidden locations, often burying it deep in the soil or stashing it in hollowed-out tree trunks.\n\nInterestingly, squirrels have an incredible memory for the locations of their caches. Research has shown that they can remember thousands of hiding spots, often returning to them months later when food is scarce. However, they don't always recover every stash some forgotten caches eventually sprout into new trees, contributing to forest regeneration. This unintentional role as forest gardeners highlights the ecological importance of squirrels in their ecosystems.\n\n## The Great Squirrel Debate: Urban vs. Wild\n\nWhile squirrels are most commonly associated with rural or wooded areas, their adaptability has allowed them to thrive in urban environments as well. In cities, squirrels have become adept at finding food sources in places like parks, streets, and even garbage cans. However, their urban counterparts face unique challenges, including traffic, predators, and the lack of natural shelters. Despite these obstacles, squirrels in urban areas are often observed using human infrastructure such as buildings, bridges, and power lines as highways for their acrobatic escapades.\n\nThere is, however, a growing concern regarding the impact of urban life on squirrel populations. Pollution, deforestation, and the loss of natural habitats are making it more difficult for squirrels to find adequate food and shelter. As a result, conservationists are focusing on creating squirrel-friendly spaces within cities, with the goal of ensuring these resourceful creatures continue to thrive in both rural and urban landscapes.\n\n## A Symbol of Resilience\n\nIn many cultures, squirrels are symbols of resourcefulness, adaptability, and preparation. Their ability to thrive in a variety of environments while navigating challenges with agility and grace serves as a reminder of the resilience inherent in nature. Whether you encounter them in a quiet forest, a city park, or your own backyard, squirrels are creatures that never fail to amaze with their endless energy and ingenuity.\n\nIn the end, squirrels may be small, but they are mighty in their ability to survive and thrive in a world that is constantly changing. So next time you spot one hopping across a branch or darting across your lawn, take a moment to appreciate the remarkable acrobat at work a true marvel of the natural world.\n", &markdown_styles())
+        });
+    user_data.documents
+        .push(Document{
+            title:"Lorem Ipsum".to_string(), 
+            path: None,
+            scroll_offset: (0.0, 0.0),
+            blocks: Vec::new()//"The Secret Life of Squirrels: Nature's Clever Acrobats\n""Squirrels are often overlooked creatures, dismissed as mere park inhabitants or backyard nuisances. Yet, beneath their fluffy tails and twitching noses lies an intricate world of cunning, agility, and survival tactics that are nothing short of fascinating. As one of the most common mammals in North America, squirrels have adapted to a wide range of environments from bustling urban centers to tranquil forests and have developed a variety of unique behaviors that continue to intrigue scientists and nature enthusiasts alike.\n""\n""Master Tree Climbers\n""At the heart of a squirrel's skill set is its impressive ability to navigate trees with ease. Whether they're darting from branch to branch or leaping across wide gaps, squirrels possess an innate talent for acrobatics. Their powerful hind legs, which are longer than their front legs, give them remarkable jumping power. With a tail that acts as a counterbalance, squirrels can leap distances of up to ten times the length of their body, making them some of the best aerial acrobats in the animal kingdom.\n""But it's not just their agility that makes them exceptional climbers. Squirrels' sharp, curved claws allow them to grip tree bark with precision, while the soft pads on their feet provide traction on slippery surfaces. Their ability to run at high speeds and scale vertical trunks with ease is a testament to the evolutionary adaptations that have made them so successful in their arboreal habitats.\n""\n""Food Hoarders Extraordinaire\n""Squirrels are often seen frantically gathering nuts, seeds, and even fungi in preparation for winter. While this behavior may seem like instinctual hoarding, it is actually a survival strategy that has been honed over millions of years. Known as \"scatter hoarding,\" squirrels store their food in a variety of hidden locations, often burying it deep in the soil or stashing it in hollowed-out tree trunks.\n""Interestingly, squirrels have an incredible memory for the locations of their caches. Research has shown that they can remember thousands of hiding spots, often returning to them months later when food is scarce. However, they don't always recover every stash some forgotten caches eventually sprout into new trees, contributing to forest regeneration. This unintentional role as forest gardeners highlights the ecological importance of squirrels in their ecosystems.\n""\n""The Great Squirrel Debate: Urban vs. Wild\n""While squirrels are most commonly associated with rural or wooded areas, their adaptability has allowed them to thrive in urban environments as well. In cities, squirrels have become adept at finding food sources in places like parks, streets, and even garbage cans. However, their urban counterparts face unique challenges, including traffic, predators, and the lack of natural shelters. Despite these obstacles, squirrels in urban areas are often observed using human infrastructure such as buildings, bridges, and power lines as highways for their acrobatic escapades.\n""There is, however, a growing concern regarding the impact of urban life on squirrel populations. Pollution, deforestation, and the loss of natural habitats are making it more difficult for squirrels to find adequate food and shelter. As a result, conservationists are focusing on creating squirrel-friendly spaces within cities, with the goal of ensuring these resourceful creatures continue to thrive in both rural and urban landscapes.\n""\n""A Symbol of Resilience\n""In many cultures, squirrels are symbols of resourcefulness, adaptability, and preparation. Their ability to thrive in a variety of environments while navigating challenges with agility and grace serves as a reminder of the resilience inherent in nature. Whether you encounter them in a quiet forest, a city park, or your own backyard, squirrels are creatures that never fail to amaze with their endless energy and ingenuity.\n""In the end, squirrels may be small, but they are mighty in their ability to survive and thrive in a world that is constantly changing. So next time you spot one hopping across a branch or darting across your lawn, take a moment to appreciate the remarkable acrobat at work a true marvel of the natural world.\n".to_string()
+        });
+}
+
+/// Where the scroll container `label` is scrolled to, once clay has laid it out.
+fn scroll_offset(clay: &Clay, label: &str) -> Option<(f32, f32)> {
+    let data = clay.scroll_container_data(clay.id(label))?;
+    // clay hands out a pointer to the position it keeps for the container
+    let position = unsafe { *data.scrollPosition };
+    Some((position.x, position.y))
+}
+
+/// Scrolls the container `label` to `offset`, returning false when clay hasn't laid it out yet.
+fn set_scroll_offset(clay: &Clay, label: &str, offset: (f32, f32)) -> bool {
+    let Some(data) = clay.scroll_container_data(clay.id(label)) else {
+        return false;
+    };
+    unsafe {
+        (*data.scrollPosition).x = offset.0;
+        (*data.scrollPosition).y = offset.1;
+    }
+    true
+}
+
+/// Remembers where main_content is scrolled to for the document it shows, and scrolls back to
+/// where a document was left whenever another one is selected.
+fn sync_document_scroll(clay: &Clay, user_data: &mut ClayState) {
+    // the file browser scrolls on its own
+    if user_data.file_browser.is_some() {
+        user_data.shown_document_index = None;
+        return;
+    }
+
+    if let (Some(index), Some(offset)) = (user_data.shown_document_index, scroll_offset(clay, "main_content")) {
+        if let Some(document) = user_data.documents.get_mut(index) {
+            document.scroll_offset = offset;
+        }
+    }
+
+    let selected = user_data.selected_document_index;
+    if user_data.shown_document_index != Some(selected) {
+        let offset = user_data.documents.get(selected).map_or((0.0, 0.0), |document| document.scroll_offset);
+        user_data.shown_document_index = set_scroll_offset(clay, "main_content", offset).then_some(selected);
+    }
+}
+
+pub fn create_layout<'a>(clay: &'a mut Clay, user_data: &mut ClayState, time_delta: f32) -> impl Iterator<Item = RenderCommand<'a>>{
+    clay.layout_dimensions(user_data.size.into());
+    clay.pointer_state(user_data.mouse_position.into(), false);
+    clay.update_scroll_containers(false, user_data.scroll_delta.into(), time_delta);
+    // the last frame has been drawn, nothing borrows the browser anymore
+    user_data.run_browser_action();
+    sync_document_scroll(clay, user_data);
+
+    clay.begin();
+
+    user_data.focus.begin_frame();
+    declare_layout(clay, user_data);
+    user_data.focus.end_frame();
+
+    clay.end()
+}
+
+pub fn declare_layout(clay: &Clay, user_data: &mut ClayState) {
+    // run once the layout is declared, so the click that ran it isn't taken for a click elsewhere
+    let mut header_command = None;
+
+    clay.with(&Declaration::new()
+            .layout_expand()
+            .id(clay.id("outer_container"))
+            .layout()
+                .direction(TopToBottom)
+                .padding(Padding::all(16))
+                .child_gap(16)
+                .end()
+            .background_color(Color::rgb(43.0, 41.0, 51.0))
+        , |_| {
+            clay.with(&Declaration::new()
+                .content_background_config()
+                .id(clay.id("header_bar"))
+                .layout()
+                    .width(grow!())
+                    .height(fixed!(120.0))
+                    .padding(Padding { left: 16, right: 16, top: 8, bottom: 8 })
+                    .child_gap(16)
+                    .child_alignment(CLAY_ALIGN_Y_CENTER)
+                    .end(),
+                |_| {
+                    let file_button = element_id(clay, "file_button");
+                    if user_data.focus.register(file_button) {
+                        user_data.file_menu_open = !user_data.file_menu_open;
+                    }
+
+                    clay.with(&Declaration::new()
+                        .id(clay.id("file_button"))
+                        .layout()
+                            .padding(Padding { left: 16, right: 16, top: 8, bottom: 8 })
+                            .end()
+                        .background_color(Color::rgb(140.0, 140.0, 140.0))
+                        .corner_radius()
+                            .all(5.0)
+                            .end(),
+                    |_| {
+                        clay.text(
+                            "File", 
+                            TextConfig::new()
+                                .font_size(16)
+                                .color(WHITE)
+                                .end()
+                        );
+
+                        let file_menu_visible = 
+                            clay.pointer_over(clay.id("file_button"))
+                            ||
+                            clay.pointer_over(clay.id("file_menu"))
+                            ||
+                            user_data.file_menu_open;
+
+                        let mut clicked_command = None;
+                        if file_menu_visible {
+                            clay.with(&Declaration::new()
+                                .id(clay.id("file_menu"))
+                                .floating()
+                                    .attach_to(FloatingAttachToElement::Parent)
+                                    .end()
+                                .layout()
+                                    .padding(Padding::new(0, 0, 8, 8))
+                                    .end()
+                                , |_| {
+                                    clay.with(&Declaration::new()
+                                        .layout()
+                                            .direction(TopToBottom)
+                                            .width(fixed!(200.0))
+                                            .end()
+                                        .background_color(Color::rgba(40.0, 40.0, 40.0, 230.0))
+                                        .corner_radius()
+                                            .all(8.0)
+                                            .end()
+                                        , |_| {
+                                            for id in FILE_MENU {
+                                                let Some(command) = user_data.commands.get(id) else {
+                                                    continue;
+                                                };
+                                                if render_dropdown_menu_item(clay, &mut user_data.focus, command, user_data.mouse_down_rising_edge) {
+                                                    clicked_command = Some(id);
+                                                }
+                                            }
+                                        }
+                                    );
+                                }
+                            );
+                        }
+                        // opened from the keyboard, the menu stays open while focus is on it
+                        let menu_focused = user_data.focus.is_focused(file_button)
+                            || FILE_MENU.iter().any(|id| user_data.focus.is_focused(element_id(clay, id)));
+                        if !menu_focused {
+                            user_data.file_menu_open = false;
+                        }
+                        if let Some(id) = clicked_command {
+                            user_data.file_menu_open = false;
+                            user_data.run_command(id);
+                        }
+                    });
+
+                    let mouse_down_rising_edge = user_data.mouse_down_rising_edge;
+                    let focus = &mut user_data.focus;
+                    let mut header_button = |text: &str, command: Option<&'static str>| {
+                        if let Some(command) = render_header_button(clay, focus, text, command, mouse_down_rising_edge) {
+                            header_command = Some(command);
+                        }
+                    };
+                    header_button("Edit", Some(COMMAND_RENAME_DOCUMENT));
+                    clay.with(&Declaration::new()
+                        .layout()
+                            .width(grow!())
+                            .end()
+                    , |_| {});
+                    header_button("Upload", Some(COMMAND_OPEN_DOCUMENT));
+                    header_button("Media", None);
+                    header_button("Support", None);
+                }
+            );
+
+            clay.with(&Declaration::new()
+                .layout_expand()
+                .id(clay.id("lower_content"))
+                .layout()
+                    .child_gap(16)
+                    .end()
+                , |_|{
+                    clay.with(&Declaration::new()
+                        .content_background_config()
+                        .id(clay.id("sidebar"))
+                        .layout()
+                            .direction(TopToBottom)
+                            .padding(Padding::all(16))
+                            .child_gap(8)
+                            .width(percent!(0.25))
+                            .height(grow!())
+                            .end()
+                        , |_| {
+                            // clicking anywhere but the title being edited keeps the new title
+                            let was_renaming = user_data.rename.is_some();
+                            if user_data.mouse_down_rising_edge && !clay.pointer_over(clay.id("rename_input")) {
+                                user_data.finish_rename(true);
+                            }
+
+                            let mut rename_clicked = None;
+                            for i in 0..user_data.documents.len() {
+                                // enter or space selects a document, and renames the selected one
+                                if user_data.focus.register(element_id_index(clay, "sidebar_document", i as u32)) {
+                                    match i == user_data.selected_document_index {
+                                        true => rename_clicked = Some(i),
+                                        false => user_data.selected_document_index = i,
+                                    }
+                                }
+
+                                let document = user_data.documents.get_mut(i).unwrap();
+                                let mut side_bar_button_layout = Declaration::new()
+                                    .id(clay.id_index("sidebar_document", i as u32))
+                                    .layout()
+                                        .width(grow!())
+                                        .padding(Padding::all(16))
+                                        .end().to_owned();
+                                
+                                if i == user_data.selected_document_index{
+                                    clay.with_styling( &mut side_bar_button_layout
+                                        .background_color(Color::rgb(120.0, 120.0, 120.0))
+                                        .corner_radius()
+                                            .all(8.0)
+                                            .end()
+                                        , |styling| {
+                                            if clay.hovered() {
+                                                // clicking the selected document again renames it
+                                                if user_data.mouse_down_rising_edge {
+                                                    rename_clicked = Some(i);
+                                                }
+
+                                                styling
+                                                    .border()
+                                                        .all_directions(3)
+                                                        .color(WHITE)
+                                                        .end();
+                                            }
+                                        }
+                                        , || {
+                                            match &user_data.rename {
+                                                Some(rename) if rename.document_index == i => {
+                                                    render_text_input(clay, "rename_input", &rename.input);
+                                                }
+                                                _ => {
+                                                    clay.text(&document.title, TextConfig::new()
+                                                        .font_size(20)
+                                                        .color(WHITE)
+                                                        .end()
+                                                    );
+                                                }
+                                            }
+                                        }
+                                    );
+                                } else {
+                                    // the same color as the sidebar, so the focus ring has something to go around
+                                    clay.with_styling(
+                                        &mut side_bar_button_layout
+                                            .background_color(Color::rgb(90.0, 90.0, 90.0))
+                                            .corner_radius()
+                                                .all(8.0)
+                                                .end(),
+                                        |styling| {
+                                            if clay.hovered() {
+                                                if user_data.mouse_down_rising_edge {
+                                                    user_data.selected_document_index = i;
+                                                }
+
+                                                styling
+                                                    .border()
+                                                        .all_directions(3)
+                                                        .color(WHITE)
+                                                        .end();
+                                                    // .background_color(Color::rgb(120.0, 120.0, 120.0))
+                                                    // .corner_radius()
+                                                    //     .all(8.0)
+                                                    //     .end();
+
+                                            }
+                                        }, 
+                                        || {
+                                            clay.text(&document.title, TextConfig::new()
+                                                .font_size(20)
+                                                .color(WHITE)
+                                                .end()
+                                            );
+                                        }
+                                    );
+                                }
+                            }
+
+                            if let Some(i) = rename_clicked.filter(|_| !was_renaming) {
+                                user_data.start_rename(i);
+                            }
+                        }
+                    );
+
+                    clay.with_styling(
+                        Declaration::new()
+                                .content_background_config()
+                                .layout_expand()
+                                .id(clay.id("main_content"))
+                                .scroll(false, true)
+                                .layout()
+                                    .direction(TopToBottom)
+                                    .child_gap(16)
+                                    .padding(Padding::all(16))
+                                    .end()
+                        , |_| {},
+                        || {
+                            if let Some(browser) = &user_data.file_browser {
+                                user_data.browser_action = render_file_browser(clay, browser, &mut user_data.focus, user_data.mouse_down_rising_edge);
+                                return;
+                            }
+
+                            let Some(selected_documtent) = user_data.documents.get(user_data.selected_document_index) else {
+                                return;
+                            };
+                            clay.text(&selected_documtent.title, 
+                                TextConfig::new()
+                                    .font_id(FONT_ID_TITLE)
+                                    .font_size(24)
+                                    .color(WHITE)
+                                    .end()
+                            );
+
+                            render_markdown(clay, user_data.ui_state.as_ref(), &selected_documtent.blocks, WHITE);
+                        }
+                    );
+                }
+            );
+        },
+    );
+
+    if let Some(id) = header_command {
+        user_data.run_command(id);
+    }
+}
@@ -0,0 +1,223 @@
+use std::path::PathBuf;
+
+use winit::application::ApplicationHandler;
+use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
+use winit::event::{ElementState, Ime, MouseScrollDelta, WindowEvent};
+use winit::event_loop::ActiveEventLoop;
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+use winit::window::{Window, WindowId};
+
+use clay_rs_wgpu::ui::ui_clipboard::Clipboard;
+
+use crate::clipboard::SystemClipboard;
+use crate::graphics_context::GraphicsContext;
+use crate::session::{Session, WindowGeometry};
+use crate::text_input::TextInputEvent;
+
+#[derive(Default)]
+pub struct App<'a> {
+    ctx: Option<GraphicsContext<'a>>,
+    clipboard: Option<SystemClipboard>,
+    modifiers: ModifiersState,
+    ime_allowed: bool,
+    /// documents to open instead of the samples or the last session
+    paths: Vec<PathBuf>,
+    session_path: Option<PathBuf>,
+    /// the last geometry of the window worth saving, minimized windows don't have one
+    window_geometry: Option<WindowGeometry>,
+}
+
+impl App<'_> {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self {
+            paths,
+            session_path: Session::default_path(),
+            ..Default::default()
+        }
+    }
+
+    /// Records the open documents and the window, for the next start.
+    fn save_session(&self) {
+        let (Some(path), Some(ctx)) = (&self.session_path, &self.ctx) else {
+            return;
+        };
+
+        let session = Session::capture(&ctx.clay_user_data, self.window_geometry);
+        if let Err(error) = session.save(path) {
+            log::warn!("could not save the session {}: {}", path.display(), error);
+        }
+    }
+
+    /// Remembers where the window is, unless it is minimized.
+    fn update_window_geometry(&mut self) {
+        if let Some(geometry) = self.ctx.as_ref().and_then(|ctx| window_geometry(&ctx.window)) {
+            self.window_geometry = Some(geometry);
+        }
+    }
+}
+
+/// The geometry of `window`, if it is worth saving.
+fn window_geometry(window: &Window) -> Option<WindowGeometry> {
+    if window.is_minimized() == Some(true) {
+        return None;
+    }
+
+    let size = window.inner_size();
+    let geometry = WindowGeometry {
+        position: window.outer_position().ok().map(|position| (position.x, position.y)),
+        width: size.width,
+        height: size.height,
+    };
+    geometry.is_usable().then_some(geometry)
+}
+
+impl<'a> ApplicationHandler for App<'a> {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        let session = self.session_path.as_deref().map(Session::load).unwrap_or_default();
+
+        let mut window_attributes = Window::default_attributes()
+            .with_title("Clay-rs-WGPU-Demo".to_string())
+            .with_inner_size(LogicalSize::new(800, 600));
+        if let Some(geometry) = session.window {
+            let monitors = event_loop.available_monitors()
+                .map(|monitor| (monitor.position().x, monitor.position().y, monitor.size().width, monitor.size().height))
+                .collect::<Vec<_>>();
+            let geometry = geometry.restored(&monitors);
+            window_attributes = window_attributes.with_inner_size(PhysicalSize::new(geometry.width, geometry.height));
+            if let Some((x, y)) = geometry.position {
+                window_attributes = window_attributes.with_position(PhysicalPosition::new(x, y));
+            }
+        }
+
+        let window = event_loop.create_window(window_attributes).unwrap();
+        self.window_geometry = window_geometry(&window).or(session.window.filter(WindowGeometry::is_usable));
+
+        let dpi_scale = window.scale_factor() as f32;
+
+        let mut state = GraphicsContext::new(window);
+        match self.paths.is_empty() {
+            true => session.restore(&mut state.clay_user_data),
+            false => state.clay_user_data.open_paths(&self.paths),
+        }
+
+        state.ui_state.borrow_mut().dpi_scale = dpi_scale;
+
+        self.ctx = Some(state);
+        self.clipboard = SystemClipboard::new();
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
+        match event {
+            WindowEvent::CloseRequested => {
+                self.save_session();
+                event_loop.exit();
+            },
+            WindowEvent::Resized(_) => {
+                self.ctx.as_mut().unwrap().resize();
+                self.update_window_geometry();
+            }
+            WindowEvent::Moved(_) => {
+                self.update_window_geometry();
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, inner_size_writer:_ } => {
+                self.ctx.as_mut().unwrap().ui_state.borrow_mut().dpi_scale = scale_factor as f32;
+            }
+            WindowEvent::RedrawRequested => {
+                self.ctx.as_mut().unwrap().render().unwrap();
+                self.ctx.as_mut().unwrap().clay_user_data.mouse_down_rising_edge = false;
+
+                // input methods only compose text while a text input is being edited
+                let ctx = self.ctx.as_ref().unwrap();
+                let editing = ctx.clay_user_data.rename.is_some();
+                if editing != self.ime_allowed {
+                    ctx.window.set_ime_allowed(editing);
+                    self.ime_allowed = editing;
+                }
+                //std::thread::sleep(Duration::from_millis(16));
+                self.ctx.as_ref().unwrap().window.request_redraw();
+            }
+            WindowEvent::MouseInput { device_id:_, state, button } => {
+                match button {
+                    winit::event::MouseButton::Left => {
+                        let ctx = self.ctx.as_mut().unwrap();
+                        ctx.clay_user_data.mouse_down_rising_edge = state.is_pressed();
+                        // the focus ring is only shown while using the keyboard
+                        if state.is_pressed() {
+                            ctx.clay_user_data.focus.clear();
+                        }
+
+                        let mut ui = ctx.ui_state.borrow_mut();
+                        match state {
+                            ElementState::Pressed => ui.selection.pointer_down(ctx.clay_user_data.mouse_position),
+                            ElementState::Released => ui.selection.pointer_up(),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            WindowEvent::MouseWheel { device_id:_, delta, phase:_ } => {
+                self.ctx.as_mut().unwrap().clay_user_data.scroll_delta = match delta {
+                    MouseScrollDelta::LineDelta(x,y ) => (x,y),
+                    MouseScrollDelta::PixelDelta(position) => position.into()
+                };
+            }
+            WindowEvent::CursorMoved { device_id:_, position } => {
+                let ctx = self.ctx.as_mut().unwrap();
+                ctx.clay_user_data.mouse_position = position.into();
+                ctx.ui_state.borrow_mut().selection.pointer_move(ctx.clay_user_data.mouse_position);
+            }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+            }
+            WindowEvent::KeyboardInput { device_id:_, event, is_synthetic:_ } => {
+                let ctx = self.ctx.as_mut().unwrap();
+                let clipboard = self.clipboard.as_mut().map(|clipboard| clipboard as &mut dyn Clipboard);
+
+                if let Some(rename) = ctx.clay_user_data.rename.as_mut() {
+                    match rename.input.handle_key(&event, self.modifiers, clipboard) {
+                        TextInputEvent::Submit => ctx.clay_user_data.finish_rename(true),
+                        TextInputEvent::Cancel => ctx.clay_user_data.finish_rename(false),
+                        TextInputEvent::None => {}
+                    }
+                    return;
+                }
+
+                if event.state.is_pressed() {
+                    if let Some(id) = ctx.clay_user_data.commands.command_for_key(&event.logical_key, self.modifiers) {
+                        ctx.clay_user_data.run_command(id);
+                        return;
+                    }
+
+                    let focus = &mut ctx.clay_user_data.focus;
+                    match &event.logical_key {
+                        Key::Named(NamedKey::Tab) if self.modifiers.shift_key() => focus.focus_previous(),
+                        Key::Named(NamedKey::Tab) => focus.focus_next(),
+                        Key::Named(NamedKey::Enter | NamedKey::Space) => focus.activate(),
+                        _ => {}
+                    }
+                }
+
+                let copy = event.state.is_pressed()
+                    && (self.modifiers.control_key() || self.modifiers.super_key())
+                    && matches!(&event.logical_key, Key::Character(c) if c.eq_ignore_ascii_case("c"));
+
+                if let Some(clipboard) = clipboard.filter(|_| copy) {
+                    ctx.ui_state.borrow().selection.copy(clipboard);
+                }
+            }
+            WindowEvent::Ime(ime) => {
+                if let Some(rename) = self.ctx.as_mut().unwrap().clay_user_data.rename.as_mut() {
+                    match ime {
+                        Ime::Preedit(text, cursor) => rename.input.set_preedit(text, cursor),
+                        Ime::Commit(text) => {
+                            rename.input.set_preedit(String::new(), None);
+                            rename.input.insert(&text);
+                        }
+                        Ime::Enabled | Ime::Disabled => {}
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
\ No newline at end of file
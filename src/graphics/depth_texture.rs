@@ -1,3 +1,4 @@
+/// The depth attachment [`crate::UIState`] expects in every render pass it draws into.
 pub struct DepthTexture{
     pub texture: wgpu::Texture,
     pub view: wgpu::TextureView,
@@ -5,18 +6,20 @@ pub struct DepthTexture{
 }
 
 impl DepthTexture {
-    pub fn new(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> Self {
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+    pub fn new(device: &wgpu::Device, size: (u32, u32)) -> Self {
         let texture = device.create_texture(
             &wgpu::TextureDescriptor {
                 size: wgpu::Extent3d { // 2.
-                    width: config.width.max(1),
-                    height: config.height.max(1),
+                    width: size.0.max(1),
+                    height: size.1.max(1),
                     depth_or_array_layers: 1,
                 },
                 mip_level_count: 1,
                 sample_count: 1,
                 dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Depth32Float,
+                format: Self::FORMAT,
                 usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
                 label: Some("depth_texture"),
                 view_formats: &[],
@@ -0,0 +1,186 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use clay_layout::Clay;
+use image::RgbaImage;
+
+use crate::ui::ui_renderer::UIState;
+use crate::ui::ui_text::measure_text;
+use super::render_target::RenderTarget;
+
+/// Renders the UI into an offscreen texture instead of a window surface,
+/// so layouts can be rendered and inspected without a display.
+pub struct HeadlessContext<'a>{
+    #[allow(dead_code)]
+    instance: wgpu::Instance,
+    adapter: wgpu::Adapter,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    size: (u32, u32),
+    target: RenderTarget,
+    readback_buffer: wgpu::Buffer,
+    padded_bytes_per_row: u32,
+
+    pub ui_state: Rc<RefCell<UIState>>,
+    pub clay: Clay<'a>,
+}
+
+impl<'a> HeadlessContext<'a> {
+    pub fn new(size: (u32, u32), dpi_scale: f32) -> Self {
+        let (instance, adapter, device, queue) = Self::request_device();
+
+        let size = (size.0.max(1), size.1.max(1));
+        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+        let target = RenderTarget::new(&device, size, format);
+
+        // rows copied out of a texture have to be aligned to 256 bytes
+        let unpadded_bytes_per_row = size.0 * 4;
+        let alignment = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(alignment) * alignment;
+
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("headless_readback_buffer"),
+            size: (padded_bytes_per_row * size.1) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let ui_state = Rc::<RefCell<UIState>>::new(RefCell::new(UIState::new(&device, &queue, format, size)));
+        ui_state.borrow_mut().dpi_scale = dpi_scale;
+
+        let mut clay = Clay::new((size.0 as f32, size.1 as f32).into());
+
+        clay.set_measure_text_function_user_data(ui_state.clone(), measure_text);
+
+        Self {
+            instance,
+            adapter,
+            device,
+            queue,
+            size,
+            target,
+            readback_buffer,
+            padded_bytes_per_row,
+
+            ui_state,
+            clay,
+        }
+    }
+
+    /// Requests a device the way [`HeadlessContext::new`] does, preferring a real gpu, for
+    /// tests of renderer parts that don't lay anything out.
+    pub fn request_device() -> (wgpu::Instance, wgpu::Adapter, wgpu::Device, wgpu::Queue) {
+        let instance = wgpu::Instance::default();
+
+        // prefer a real gpu, but fall back to a software adapter on machines without one
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        })).or_else(|| pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            compatible_surface: None,
+            force_fallback_adapter: true,
+        }))).expect("no wgpu adapter available, not even a fallback adapter");
+
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+                memory_hints: wgpu::MemoryHints::default()
+            },
+            None,
+        )).unwrap();
+
+        (instance, adapter, device, queue)
+    }
+
+    pub fn adapter_info(&self) -> wgpu::AdapterInfo {
+        self.adapter.get_info()
+    }
+
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    /// The texture the last layout was rendered into, still on the gpu.
+    pub fn target(&self) -> &RenderTarget {
+        &self.target
+    }
+
+    /// Declares a layout through `layout`, renders it over a red background and reads it back.
+    pub fn render_layout(&mut self, layout: impl FnOnce(&Clay)) -> RgbaImage {
+        let mut command_encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label:Some("Headless Render Encoder"),
+        });
+
+        self.clay.layout_dimensions((self.size.0 as f32, self.size.1 as f32).into());
+        self.clay.begin();
+        layout(&self.clay);
+        let render_commands = self.clay.end();
+
+        self.ui_state.borrow_mut().render_clay_to_target(
+            render_commands,
+            &self.target,
+            Some(wgpu::Color {
+                r: 1.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            }),
+            &mut command_encoder,
+            &self.device,
+            &self.queue,
+        );
+
+        command_encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: self.target.texture(),
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &self.readback_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.padded_bytes_per_row),
+                    rows_per_image: Some(self.size.1),
+                },
+            },
+            wgpu::Extent3d {
+                width: self.size.0,
+                height: self.size.1,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        self.queue.submit(std::iter::once(command_encoder.finish()));
+
+        self.read_back()
+    }
+
+    fn read_back(&self) -> RgbaImage {
+        let slice = self.readback_buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        self.device.poll(wgpu::Maintain::Wait);
+
+        let unpadded_bytes_per_row = (self.size.0 * 4) as usize;
+        let mut pixels = Vec::<u8>::with_capacity(unpadded_bytes_per_row * self.size.1 as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(self.padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row]);
+            }
+        }
+        self.readback_buffer.unmap();
+
+        RgbaImage::from_raw(self.size.0, self.size.1, pixels).unwrap()
+    }
+}
@@ -1,2 +1,3 @@
-pub mod graphics_context;
-pub mod depth_texture;
\ No newline at end of file
+pub mod depth_texture;
+pub mod headless_context;
+pub mod render_target;
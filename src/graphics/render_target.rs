@@ -0,0 +1,97 @@
+use super::depth_texture::DepthTexture;
+
+/// A texture the ui can be rendered into instead of a window surface, for compositing onto
+/// 3d geometry, thumbnails or post processing.
+///
+/// The target owns the depth attachment the renderer needs, sized along with the color texture.
+/// The color format has to match the format the [`crate::UIState`] drawing into it was
+/// created with, targets of another format need a renderer of their own.
+pub struct RenderTarget {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    depth_texture: DepthTexture,
+}
+
+impl RenderTarget {
+    /// Creates a target that can also be sampled and copied from.
+    pub fn new(device: &wgpu::Device, size: (u32, u32), format: wgpu::TextureFormat) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("ui_render_target"),
+            size: wgpu::Extent3d {
+                width: size.0.max(1),
+                height: size.1.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        Self::from_texture(device, texture)
+    }
+
+    /// Wraps a texture created by the application, which needs `RENDER_ATTACHMENT` usage.
+    pub fn from_texture(device: &wgpu::Device, texture: wgpu::Texture) -> Self {
+        assert!(
+            texture.usage().contains(wgpu::TextureUsages::RENDER_ATTACHMENT),
+            "ui render targets need RENDER_ATTACHMENT usage"
+        );
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_texture = DepthTexture::new(device, (texture.width(), texture.height()));
+
+        Self {
+            texture,
+            view,
+            depth_texture,
+        }
+    }
+
+    /// Recreates the color and depth textures at the new size, with the same format and usage.
+    /// The previous contents are lost.
+    pub fn resize(&mut self, device: &wgpu::Device, size: (u32, u32)) {
+        if size == self.size() {
+            return;
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("ui_render_target"),
+            size: wgpu::Extent3d {
+                width: size.0.max(1),
+                height: size.1.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.texture.format(),
+            usage: self.texture.usage(),
+            view_formats: &[],
+        });
+
+        *self = Self::from_texture(device, texture);
+    }
+
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    pub fn depth_view(&self) -> &wgpu::TextureView {
+        &self.depth_texture.view
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.texture.width(), self.texture.height())
+    }
+
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.texture.format()
+    }
+}
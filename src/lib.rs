@@ -0,0 +1,13 @@
+//! Renders [Clay](https://github.com/nicbarker/clay) layouts with wgpu.
+//!
+//! [`UIState`] is the renderer. It only needs the device, queue and texture format of an
+//! existing wgpu setup, and draws a frame of clay render commands into a render pass the
+//! application owns, so it fits next to whatever else the application renders. The `demo`
+//! example shows it driven by a winit window.
+
+pub mod graphics;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod ui;
+
+pub use ui::ui_renderer::UIState;
@@ -0,0 +1,6 @@
+//! Helpers for checking rendered layouts against reference images, used by the snapshot tests.
+//!
+//! Only built with the `testing` feature. References are read from and written to this
+//! crate's own `tests/snapshots`, so the harness is of no use to other crates.
+
+pub mod snapshot;
@@ -0,0 +1,181 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use clay_layout::Clay;
+use image::{Rgba, RgbaImage};
+
+use crate::graphics::headless_context::HeadlessContext;
+use crate::ui::ui_renderer::UIState;
+use crate::ui::ui_software_renderer::SoftwareRenderer;
+use crate::ui::ui_text::measure_text;
+
+// clay keeps its context in a global, so only one layout can be built at a time
+static CLAY_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct SnapshotConfig {
+    pub size: (u32, u32),
+    pub dpi_scale: f32,
+    /// largest difference allowed on any channel before a pixel counts as mismatched
+    pub tolerance: u8,
+    /// number of mismatched pixels allowed before the snapshot fails
+    pub max_mismatched_pixels: usize,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            size: (400, 300),
+            dpi_scale: 1.0,
+            tolerance: 2,
+            max_mismatched_pixels: 0,
+        }
+    }
+}
+
+pub struct SnapshotComparison {
+    pub mismatched_pixels: usize,
+    pub diff: RgbaImage,
+}
+
+pub fn compare_images(expected: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> Result<SnapshotComparison, String> {
+    if expected.dimensions() != actual.dimensions() {
+        return Err(format!(
+            "size mismatch: expected {:?}, got {:?}",
+            expected.dimensions(),
+            actual.dimensions()
+        ));
+    }
+
+    let mut diff = RgbaImage::new(expected.width(), expected.height());
+    let mut mismatched_pixels = 0;
+
+    for ((expected_pixel, actual_pixel), diff_pixel) in expected.pixels().zip(actual.pixels()).zip(diff.pixels_mut()) {
+        let mismatched = expected_pixel.0.iter()
+            .zip(actual_pixel.0.iter())
+            .any(|(e, a)| e.abs_diff(*a) > tolerance);
+
+        *diff_pixel = match mismatched {
+            true => {
+                mismatched_pixels += 1;
+                Rgba([255, 0, 255, 255])
+            }
+            false => {
+                // faded copy of the reference so the mismatches stand out
+                let [r, g, b, _] = expected_pixel.0;
+                let luma = ((r as u32 + g as u32 + b as u32) / 3) as u8;
+                Rgba([luma / 4, luma / 4, luma / 4, 255])
+            }
+        };
+    }
+
+    Ok(SnapshotComparison {
+        mismatched_pixels,
+        diff,
+    })
+}
+
+fn reference_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots").join(format!("{name}.png"))
+}
+
+fn output_path(name: &str, suffix: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target").join("snapshots").join(format!("{name}.{suffix}.png"))
+}
+
+pub fn render_snapshot(config: &SnapshotConfig, layout: impl FnOnce(&Clay)) -> RgbaImage {
+    render_snapshot_with_state(config, |clay, _| layout(clay))
+}
+
+/// Same as [`render_snapshot`], with the renderer state handed to `layout` for layouts
+/// that load images or rich text into it first.
+pub fn render_snapshot_with_state(config: &SnapshotConfig, layout: impl FnOnce(&Clay, &Rc<RefCell<UIState>>)) -> RgbaImage {
+    let _guard = CLAY_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut ctx = HeadlessContext::new(config.size, config.dpi_scale);
+    let ui_state = ctx.ui_state.clone();
+    ctx.render_layout(|clay| layout(clay, &ui_state))
+}
+
+pub fn render_software_snapshot(config: &SnapshotConfig, layout: impl FnOnce(&Clay)) -> RgbaImage {
+    let _guard = CLAY_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let renderer = Rc::new(RefCell::new(SoftwareRenderer::new(config.size)));
+    renderer.borrow_mut().dpi_scale = config.dpi_scale;
+
+    let mut clay = Clay::new((config.size.0 as f32, config.size.1 as f32).into());
+    clay.set_measure_text_function_user_data(renderer.clone(), measure_text);
+
+    clay.begin();
+    layout(&clay);
+    let render_commands = clay.end();
+    renderer.borrow_mut().render_clay(render_commands);
+
+    let image = renderer.borrow().to_image();
+    image
+}
+
+/// Renders `layout` on the gpu and compares it against `tests/snapshots/<name>.png`.
+///
+/// A missing reference fails the test. Setting `UPDATE_SNAPSHOTS=1` records
+/// missing references and re-records the existing ones from the current output.
+/// On failure the actual and diff images are written to `target/snapshots/`.
+pub fn assert_snapshot(name: &str, config: &SnapshotConfig, layout: impl FnOnce(&Clay)) {
+    check_snapshot(name, config, render_snapshot(config, layout));
+}
+
+/// Same as [`assert_snapshot`], with the renderer state handed to `layout`.
+pub fn assert_snapshot_with_state(name: &str, config: &SnapshotConfig, layout: impl FnOnce(&Clay, &Rc<RefCell<UIState>>)) {
+    check_snapshot(name, config, render_snapshot_with_state(config, layout));
+}
+
+/// Same as [`assert_snapshot`], but rasterized by the [`SoftwareRenderer`].
+pub fn assert_software_snapshot(name: &str, config: &SnapshotConfig, layout: impl FnOnce(&Clay)) {
+    check_snapshot(name, config, render_software_snapshot(config, layout));
+}
+
+fn check_snapshot(name: &str, config: &SnapshotConfig, actual: RgbaImage) {
+    let reference = reference_path(name);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(reference.parent().unwrap()).unwrap();
+        actual.save(&reference).unwrap();
+        return;
+    }
+
+    let actual_path = output_path(name, "actual");
+    std::fs::create_dir_all(actual_path.parent().unwrap()).unwrap();
+
+    if !reference.exists() {
+        actual.save(&actual_path).unwrap();
+        panic!(
+            "snapshot {name}: no reference image at {}, actual output saved to {}; run with UPDATE_SNAPSHOTS=1 to record it",
+            reference.display(),
+            actual_path.display()
+        );
+    }
+
+    let expected = image::open(&reference).unwrap().to_rgba8();
+
+    let comparison = match compare_images(&expected, &actual, config.tolerance) {
+        Ok(comparison) => comparison,
+        Err(message) => {
+            actual.save(&actual_path).unwrap();
+            panic!("snapshot {name}: {message}, actual output saved to {}", actual_path.display());
+        }
+    };
+
+    if comparison.mismatched_pixels > config.max_mismatched_pixels {
+        let diff_path = output_path(name, "diff");
+        actual.save(&actual_path).unwrap();
+        comparison.diff.save(&diff_path).unwrap();
+        panic!(
+            "snapshot {name}: {} pixels differ by more than {} (allowed {}), see {} and {}",
+            comparison.mismatched_pixels,
+            config.tolerance,
+            config.max_mismatched_pixels,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
@@ -1,3 +1,11 @@
 pub mod ui_pipeline;
 pub mod ui_renderer;
-pub mod ui_layout;
\ No newline at end of file
+pub mod ui_text;
+pub mod ui_software_renderer;
+pub mod ui_images;
+pub mod ui_custom;
+pub mod ui_fonts;
+pub mod ui_rich_text;
+pub mod ui_clipboard;
+pub mod ui_selection;
+pub mod ui_focus;
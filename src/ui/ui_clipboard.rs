@@ -0,0 +1,30 @@
+/// Where copied text goes and pasted text comes from.
+///
+/// The renderer has no access to the system clipboard, the application passes in whatever it
+/// uses for that. Tests use a [`MemoryClipboard`].
+pub trait Clipboard {
+    fn get_text(&mut self) -> Option<String>;
+    fn set_text(&mut self, text: &str);
+}
+
+/// A clipboard that only exists in memory, shared by nothing but its owner.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryClipboard {
+    text: Option<String>,
+}
+
+impl MemoryClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Clipboard for MemoryClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.text.clone()
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.text = Some(text.to_string());
+    }
+}
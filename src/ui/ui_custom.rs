@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::ffi::c_void;
+
+/// Everything a custom element callback gets to draw with.
+///
+/// The render pass is the one the ui is drawn into, so whatever the callback records
+/// lands between the ui content declared before and after the element. The scissor
+/// rect is already set to the visible part of the element and is restored afterwards,
+/// as are the pipeline and bind groups the ui uses.
+pub struct CustomRenderContext<'a, 'pass> {
+    /// x, y, width, height in pixels
+    pub bounding_box: [f32; 4],
+    /// x, y, width, height in pixels of the part of the element that is not scrolled out of view
+    pub clip: [f32; 4],
+    /// the depth the ui used for this element, for callbacks that depth test against the ui
+    pub depth: f32,
+    /// width and height of the render target in pixels
+    pub target_size: (u32, u32),
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    pub render_pass: &'a mut wgpu::RenderPass<'pass>,
+}
+
+pub type CustomRenderCallback = Box<dyn FnMut(&mut CustomRenderContext<'_, '_>)>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CustomElementId(u32);
+
+impl CustomElementId {
+    /// The custom data to give a clay custom element to draw it through this callback.
+    pub fn custom_data(self) -> *mut c_void {
+        // offset by one, clay reports a null pointer for custom elements without data
+        (self.0 as usize + 1) as *mut c_void
+    }
+
+    /// The callback a clay custom element was given through [`CustomElementId::custom_data`].
+    pub fn from_custom_data(data: *mut c_void) -> Option<Self> {
+        (data as usize).checked_sub(1).map(|id| CustomElementId(id as u32))
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct CustomCommand {
+    pub element: CustomElementId,
+    pub bounding_box: [f32; 4],
+    pub clip: [f32; 4],
+    pub depth: f32,
+}
+
+/// Callbacks that draw the contents of clay custom elements.
+///
+/// Like [`crate::ui::ui_images::UIImages`], elements name their callback through their
+/// custom data, see [`CustomElementId::custom_data`]. Data the callback needs, a mesh, a
+/// camera or a video frame, is simply captured by the closure. Custom elements without a
+/// callback draw nothing.
+pub struct UICustomElements {
+    callbacks: HashMap<CustomElementId, CustomRenderCallback>,
+    next_id: u32,
+}
+
+impl UICustomElements {
+    pub fn new() -> Self {
+        Self {
+            callbacks: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Registers `callback` to draw the clay custom elements given the returned id as their data.
+    ///
+    /// Buffer writes through the queue take effect before the frame is submitted, so a
+    /// callback that updates its own uniforms every frame can do so from inside the callback.
+    pub fn register(&mut self, callback: impl FnMut(&mut CustomRenderContext<'_, '_>) + 'static) -> CustomElementId {
+        let id = CustomElementId(self.next_id);
+        self.next_id += 1;
+        self.callbacks.insert(id, Box::new(callback));
+        id
+    }
+
+    pub fn unregister(&mut self, element: CustomElementId) {
+        self.callbacks.remove(&element);
+    }
+
+    pub fn is_registered(&self, element: CustomElementId) -> bool {
+        self.callbacks.contains_key(&element)
+    }
+
+    pub(crate) fn draw(&mut self, command: &CustomCommand, target_size: (u32, u32), device: &wgpu::Device, queue: &wgpu::Queue, render_pass: &mut wgpu::RenderPass) {
+        let Some(callback) = self.callbacks.get_mut(&command.element) else {
+            return;
+        };
+
+        let left = command.clip[0].max(0.0).floor() as u32;
+        let top = command.clip[1].max(0.0).floor() as u32;
+        let right = ((command.clip[0] + command.clip[2]).ceil().max(0.0) as u32).min(target_size.0);
+        let bottom = ((command.clip[1] + command.clip[3]).ceil().max(0.0) as u32).min(target_size.1);
+        if right <= left || bottom <= top {
+            return;
+        }
+
+        render_pass.set_scissor_rect(left, top, right - left, bottom - top);
+
+        callback(&mut CustomRenderContext {
+            bounding_box: command.bounding_box,
+            clip: command.clip,
+            depth: command.depth,
+            target_size,
+            device,
+            queue,
+            render_pass,
+        });
+
+        render_pass.set_viewport(0.0, 0.0, target_size.0 as f32, target_size.1 as f32, 0.0, 1.0);
+        render_pass.set_scissor_rect(0, 0, target_size.0, target_size.1);
+    }
+}
+
+impl Default for UICustomElements {
+    fn default() -> Self {
+        Self::new()
+    }
+}
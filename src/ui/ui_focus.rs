@@ -0,0 +1,114 @@
+use crate::ui::ui_renderer::UIColor;
+
+/// Which element has keyboard focus, moved with Tab and Shift+Tab.
+///
+/// Elements are identified by their clay element ids. Every frame the layout registers the
+/// elements that can take focus, in the order Tab visits them, and finds out whether the
+/// focused one was activated with Enter or Space since the last frame.
+#[derive(Clone, Debug, Default)]
+pub struct UIFocus {
+    order: Vec<u32>,
+    focused: Option<u32>,
+    activated: Option<u32>,
+}
+
+impl UIFocus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts registering the focusable elements of a new frame.
+    pub fn begin_frame(&mut self) {
+        self.order.clear();
+    }
+
+    /// Drops focus from an element that was not registered this frame, and any activation
+    /// that nothing registered to pick up.
+    pub fn end_frame(&mut self) {
+        if self.focused.is_some_and(|id| !self.order.contains(&id)) {
+            self.focused = None;
+        }
+        self.activated = None;
+    }
+
+    /// Registers `element_id` as the next focusable element, returning whether it was
+    /// activated since the last frame.
+    pub fn register(&mut self, element_id: u32) -> bool {
+        self.order.push(element_id);
+
+        match self.activated == Some(element_id) {
+            true => {
+                self.activated = None;
+                true
+            }
+            false => false,
+        }
+    }
+
+    pub fn focused(&self) -> Option<u32> {
+        self.focused
+    }
+
+    pub fn is_focused(&self, element_id: u32) -> bool {
+        self.focused == Some(element_id)
+    }
+
+    pub fn focus(&mut self, element_id: u32) {
+        self.focused = Some(element_id);
+    }
+
+    pub fn clear(&mut self) {
+        self.focused = None;
+    }
+
+    /// Moves focus to the element after the focused one, wrapping around at the end.
+    /// Without focus, the first element is focused.
+    pub fn focus_next(&mut self) {
+        self.focused = match self.focused_index() {
+            Some(index) => self.order.get((index + 1) % self.order.len()).copied(),
+            None => self.order.first().copied(),
+        };
+    }
+
+    /// Moves focus to the element before the focused one, wrapping around at the start.
+    /// Without focus, the last element is focused.
+    pub fn focus_previous(&mut self) {
+        self.focused = match self.focused_index() {
+            Some(index) => self.order.get((index + self.order.len() - 1) % self.order.len()).copied(),
+            None => self.order.last().copied(),
+        };
+    }
+
+    /// Activates the focused element, which finds out when it registers next frame.
+    pub fn activate(&mut self) {
+        self.activated = self.focused;
+    }
+
+    fn focused_index(&self) -> Option<usize> {
+        self.focused.and_then(|id| self.order.iter().position(|&element_id| element_id == id))
+    }
+}
+
+/// The outline the renderer draws around the element with keyboard focus.
+///
+/// The element is found by the id on its render commands, so it needs a background,
+/// a border or an image for the ring to be drawn.
+#[derive(Copy, Clone, Debug)]
+pub struct UIFocusRing {
+    pub element_id: Option<u32>,
+    pub color: UIColor,
+    pub width: f32,
+    /// gap between the element and the ring
+    pub offset: f32,
+}
+
+impl Default for UIFocusRing {
+    fn default() -> Self {
+        Self {
+            element_id: None,
+            color: UIColor { r: 0.3, g: 0.6, b: 1.0, a: 1.0 },
+            width: 2.0,
+            offset: 2.0,
+        }
+    }
+}
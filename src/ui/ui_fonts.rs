@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use glyphon::cosmic_text::{fontdb, Attrs, FamilyOwned, FontSystem, Style, Weight};
+
+/// Lines drawn through text by the ui pipeline, in the text color.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextDecoration {
+    pub underline: bool,
+    pub strikethrough: bool,
+}
+
+impl TextDecoration {
+    const UNDERLINE: usize = 1;
+    const STRIKETHROUGH: usize = 2;
+
+    /// The decoration as glyph metadata, so it survives shaping along with each glyph.
+    pub fn metadata(&self) -> usize {
+        let mut metadata = 0;
+        if self.underline {
+            metadata |= Self::UNDERLINE;
+        }
+        if self.strikethrough {
+            metadata |= Self::STRIKETHROUGH;
+        }
+        metadata
+    }
+
+    pub fn from_metadata(metadata: usize) -> Self {
+        Self {
+            underline: metadata & Self::UNDERLINE != 0,
+            strikethrough: metadata & Self::STRIKETHROUGH != 0,
+        }
+    }
+}
+
+/// How text declared with one clay font id is drawn: a family plus the weight and style to
+/// pick from it, and the decoration of the text.
+///
+/// Clay only hands the font id through to the renderer, so styles like links and headings
+/// get an id of their own.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UIFont {
+    pub family: FamilyOwned,
+    pub weight: Weight,
+    pub style: Style,
+    pub decoration: TextDecoration,
+}
+
+impl UIFont {
+    pub fn family(name: &str) -> Self {
+        Self::generic(FamilyOwned::Name(name.into()))
+    }
+
+    pub fn sans_serif() -> Self {
+        Self::generic(FamilyOwned::SansSerif)
+    }
+
+    pub fn serif() -> Self {
+        Self::generic(FamilyOwned::Serif)
+    }
+
+    pub fn monospace() -> Self {
+        Self::generic(FamilyOwned::Monospace)
+    }
+
+    fn generic(family: FamilyOwned) -> Self {
+        Self {
+            family,
+            weight: Weight::NORMAL,
+            style: Style::Normal,
+            decoration: TextDecoration::default(),
+        }
+    }
+
+    pub fn weight(mut self, weight: Weight) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn bold(self) -> Self {
+        self.weight(Weight::BOLD)
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.style = Style::Italic;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.decoration.underline = true;
+        self
+    }
+
+    pub fn strikethrough(mut self) -> Self {
+        self.decoration.strikethrough = true;
+        self
+    }
+
+    pub fn attrs(&self) -> Attrs<'_> {
+        Attrs::new()
+            .family(self.family.as_family())
+            .weight(self.weight)
+            .style(self.style)
+            .metadata(self.decoration.metadata())
+    }
+}
+
+impl Default for UIFont {
+    fn default() -> Self {
+        Self::sans_serif()
+    }
+}
+
+/// Maps clay font ids to faces, and loads font files into the font system.
+///
+/// Font id 0 and any id that was never registered use the default face, the system sans serif
+/// unless [`UIFonts::set_default`] says otherwise.
+pub struct UIFonts {
+    faces: HashMap<u16, UIFont>,
+    default: UIFont,
+}
+
+impl UIFonts {
+    pub fn new() -> Self {
+        Self {
+            faces: HashMap::new(),
+            default: UIFont::default(),
+        }
+    }
+
+    /// Draws and measures text declared with `font_id` in `font`.
+    pub fn register(&mut self, font_id: u16, font: UIFont) {
+        self.faces.insert(font_id, font);
+    }
+
+    pub fn set_default(&mut self, font: UIFont) {
+        self.default = font;
+    }
+
+    pub fn get(&self, font_id: u16) -> &UIFont {
+        self.faces.get(&font_id).unwrap_or(&self.default)
+    }
+}
+
+impl Default for UIFonts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Loads a ttf, otf or collection file, returning the family names it contains so they can
+/// be registered with [`UIFont::family`].
+pub fn load_font_file(font_system: &mut FontSystem, path: impl AsRef<Path>) -> std::io::Result<Vec<String>> {
+    Ok(load_font_data(font_system, std::fs::read(path)?))
+}
+
+/// Loads a font from memory, for fonts embedded with `include_bytes!`.
+pub fn load_font_data(font_system: &mut FontSystem, data: Vec<u8>) -> Vec<String> {
+    let database = font_system.db_mut();
+    let ids = database.load_font_source(fontdb::Source::Binary(Arc::new(data)));
+
+    let mut families = Vec::<String>::new();
+    for id in ids {
+        if let Some(face) = database.face(id) {
+            for (family, _) in face.families.iter() {
+                if !families.contains(family) {
+                    families.push(family.clone());
+                }
+            }
+        }
+    }
+
+    if families.is_empty() {
+        log::warn!("no font faces found in the loaded font data");
+    }
+
+    families
+}
+
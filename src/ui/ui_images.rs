@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::path::Path;
+
+use etagere::{size2, AtlasAllocator, AllocId};
+use image::RgbaImage;
+
+const ATLAS_SIZE: u32 = 2048;
+
+/// Images that have not been drawn for this many frames give their atlas space back.
+const EVICT_AFTER_FRAMES: u64 = 600;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ImageId(u32);
+
+impl ImageId {
+    /// The image data to give a clay image element to draw this image.
+    pub fn image_data(self) -> *mut c_void {
+        // offset by one, clay reports a null pointer for image elements without data
+        (self.0 as usize + 1) as *mut c_void
+    }
+
+    /// The image a clay image element was given through [`ImageId::image_data`].
+    pub fn from_image_data(data: *mut c_void) -> Option<Self> {
+        (data as usize).checked_sub(1).map(|id| ImageId(id as u32))
+    }
+}
+
+struct ImageEntry {
+    pixels: RgbaImage,
+    allocation: Option<(AllocId, [f32; 4])>,
+    last_used: u64,
+}
+
+/// Decoded images, packed into a single gpu texture on demand.
+///
+/// Clay image elements name the image they show through their image data, see
+/// [`ImageId::image_data`].
+pub struct UIImages {
+    texture: wgpu::Texture,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub bind_group: wgpu::BindGroup,
+    allocator: AtlasAllocator,
+    images: HashMap<ImageId, ImageEntry>,
+    next_id: u32,
+    frame: u64,
+}
+
+impl UIImages {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("ui_image_atlas"),
+            size: wgpu::Extent3d {
+                width: ATLAS_SIZE,
+                height: ATLAS_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("ui_image_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("ui_image_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        Self {
+            texture,
+            bind_group_layout,
+            bind_group,
+            allocator: AtlasAllocator::new(size2(ATLAS_SIZE as i32, ATLAS_SIZE as i32)),
+            images: HashMap::new(),
+            next_id: 0,
+            frame: 0,
+        }
+    }
+
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<ImageId, image::ImageError> {
+        Ok(self.insert(image::open(path)?.to_rgba8()))
+    }
+
+    pub fn load_memory(&mut self, bytes: &[u8]) -> Result<ImageId, image::ImageError> {
+        Ok(self.insert(image::load_from_memory(bytes)?.to_rgba8()))
+    }
+
+    pub fn insert(&mut self, pixels: RgbaImage) -> ImageId {
+        let id = ImageId(self.next_id);
+        self.next_id += 1;
+
+        self.images.insert(id, ImageEntry {
+            pixels,
+            allocation: None,
+            last_used: self.frame,
+        });
+
+        id
+    }
+
+    pub fn remove(&mut self, image: ImageId) {
+        if let Some(entry) = self.images.remove(&image) {
+            if let Some((allocation, _)) = entry.allocation {
+                self.allocator.deallocate(allocation);
+            }
+        }
+    }
+
+    pub fn contains(&self, image: ImageId) -> bool {
+        self.images.contains_key(&image)
+    }
+
+    /// Returns the atlas rectangle of `image` as u, v, width, height, uploading it first
+    /// if it is not resident.
+    pub fn prepare(&mut self, queue: &wgpu::Queue, image: ImageId) -> Option<[f32; 4]> {
+        let entry = self.images.get(&image)?;
+
+        if entry.allocation.is_none() {
+            let (width, height) = entry.pixels.dimensions();
+            if width + 2 > ATLAS_SIZE || height + 2 > ATLAS_SIZE {
+                log::warn!("image {:?} is {}x{}, larger than the {}px image atlas", image, width, height, ATLAS_SIZE);
+                return None;
+            }
+            let allocation = self.allocate(width, height)?;
+            let entry = self.images.get_mut(&image).unwrap();
+            let origin = allocation.rectangle.min;
+
+            // one pixel of padding on every side keeps neighbours from bleeding in when filtering
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &self.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d { x: origin.x as u32 + 1, y: origin.y as u32 + 1, z: 0 },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                entry.pixels.as_raw(),
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(width * 4),
+                    rows_per_image: Some(height),
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+
+            let uv = [
+                (origin.x as f32 + 1.0) / ATLAS_SIZE as f32,
+                (origin.y as f32 + 1.0) / ATLAS_SIZE as f32,
+                width as f32 / ATLAS_SIZE as f32,
+                height as f32 / ATLAS_SIZE as f32,
+            ];
+            entry.allocation = Some((allocation.id, uv));
+        }
+
+        let entry = self.images.get_mut(&image).unwrap();
+        entry.last_used = self.frame;
+        entry.allocation.map(|(_, uv)| uv)
+    }
+
+    fn allocate(&mut self, width: u32, height: u32) -> Option<etagere::Allocation> {
+        let size = size2(width as i32 + 2, height as i32 + 2);
+
+        loop {
+            if let Some(allocation) = self.allocator.allocate(size) {
+                return Some(allocation);
+            }
+
+            // make room by dropping the least recently drawn image that is not in use this frame
+            let oldest = self.images.iter()
+                .filter(|(_, entry)| entry.allocation.is_some() && entry.last_used < self.frame)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(id, _)| *id);
+
+            match oldest {
+                Some(id) => self.evict(id),
+                None => {
+                    log::warn!("image atlas is full, skipping a {}x{} image", width, height);
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn evict(&mut self, image: ImageId) {
+        if let Some(entry) = self.images.get_mut(&image) {
+            if let Some((allocation, _)) = entry.allocation.take() {
+                self.allocator.deallocate(allocation);
+            }
+        }
+    }
+
+    /// Called once per frame, frees atlas space held by images that have not been drawn recently.
+    pub fn end_frame(&mut self) {
+        let frame = self.frame;
+        let stale: Vec<ImageId> = self.images.iter()
+            .filter(|(_, entry)| entry.allocation.is_some() && frame - entry.last_used > EVICT_AFTER_FRAMES)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for image in stale {
+            self.evict(image);
+        }
+
+        self.frame += 1;
+    }
+}
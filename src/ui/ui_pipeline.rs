@@ -1,13 +1,15 @@
 pub struct UIPipeline {
     pixel_format: wgpu::TextureFormat,
     vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout<'static>>,
+    bind_group_layouts: Vec<wgpu::BindGroupLayout>,
 }
 
 impl UIPipeline {
     pub fn new(pixel_format: wgpu::TextureFormat) -> Self {
         Self {
             pixel_format,
-            vertex_buffer_layouts: Vec::new()
+            vertex_buffer_layouts: Vec::new(),
+            bind_group_layouts: Vec::new(),
         }
     }
 
@@ -15,6 +17,10 @@ impl UIPipeline {
         self.vertex_buffer_layouts.push(layout);
     }
 
+    pub fn add_bind_group_layout(&mut self, layout: wgpu::BindGroupLayout) {
+        self.bind_group_layouts.push(layout);
+    }
+
     pub fn build_pipeline(&self, device: &wgpu::Device) -> wgpu::RenderPipeline {
         // let mut filepath = current_dir().unwrap();
         // filepath.push(self.shader_file.as_str());
@@ -29,16 +35,19 @@ impl UIPipeline {
         };
         let shader_module = device.create_shader_module(shader_module_desc);
 
+        let bind_group_layouts: Vec<&wgpu::BindGroupLayout> = self.bind_group_layouts.iter().collect();
+
         let piplaydesc = wgpu::PipelineLayoutDescriptor{
             label: Some("UI Render Pipeline Layout"),
-            bind_group_layouts: &[],
+            bind_group_layouts: &bind_group_layouts,
             push_constant_ranges: &[],
         };
         let pipeline_layout = device.create_pipeline_layout(&piplaydesc);
 
         let render_targets = [Some(wgpu::ColorTargetState{
             format: self.pixel_format,
-            blend: Some(wgpu::BlendState::REPLACE),
+            // the shader outputs premultiplied alpha, with edge coverage folded in
+            blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
             write_mask: wgpu::ColorWrites::ALL,
         })];
 
@@ -1,22 +1,28 @@
 use glyphon::{
-    Attrs, Buffer, Cache, cosmic_text, Color, Family, FontSystem, Metrics, Resolution, Shaping, SwashCache,
-    TextArea, TextAtlas, TextBounds, TextRenderer, Viewport,
+    Cache, cosmic_text, Color, Resolution, TextArea, TextAtlas, TextBounds, TextRenderer, Viewport,
 };
 use wgpu::util::DeviceExt;
 use wgpu::MultisampleState;
-use winit::dpi::PhysicalSize;
 use core::f32;
-use std::ops::{Add, Mul, Sub};
+use std::ops::{Add, Mul, Range, Sub};
+use std::rc::Rc;
 
 use clay_layout::render_commands::RenderCommand;
 use clay_layout::math::Dimensions;
 
+use crate::graphics::render_target::RenderTarget;
+use crate::ui::ui_custom::{CustomCommand, CustomElementId, UICustomElements};
+use crate::ui::ui_focus::UIFocusRing;
+use crate::ui::ui_images::{ImageId, UIImages};
 use crate::ui::ui_pipeline::UIPipeline;
+use crate::ui::ui_selection::UISelection;
+use crate::ui::ui_text::{decoration_rects, range_rects, spaced_glyphs, MeasureText, TextShaping, TextWrap, UIText};
 
 pub struct TextLine {
-    line: glyphon::Buffer,
+    line: Rc<glyphon::Buffer>,
     left: f32,
     top: f32,
+    depth: f32,
     color: Color,
     bounds: Option<(UIPosition, UIPosition)>,
 }
@@ -31,7 +37,7 @@ pub struct UICornerRadii {
 
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
-pub struct UIColor{pub r: f32, pub g: f32, pub b:f32}
+pub struct UIColor{pub r: f32, pub g: f32, pub b:f32, pub a:f32}
 
 pub struct UIBorderThickness{
     pub top:f32,
@@ -40,7 +46,7 @@ pub struct UIBorderThickness{
     pub right:f32,
 }
 
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
 pub struct UIPosition{pub x:f32, pub y:f32, pub z:f32}
 
@@ -152,121 +158,261 @@ pub struct UISize {
     pub height:f32
 }
 
+/// One rounded box, expanded to a quad in the vertex shader and cut out
+/// as a signed distance field in the fragment shader.
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
-pub struct UIVertex {
-    pub position: UIPosition,
+pub struct UIInstance {
+    /// x, y, width, height
+    pub rect: [f32; 4],
+    /// top left, top right, bottom right, bottom left
+    pub radii: [f32; 4],
+    /// top, right, bottom, left; all zero fills the shape
+    pub border: [f32; 4],
     pub color: UIColor,
-    pub size: UISize
+    /// u, v, width, height in the image atlas; zero width draws the plain color
+    pub uv: [f32; 4],
+    /// x, y, width, height of the region the instance is clipped to
+    pub clip: [f32; 4],
+    pub depth: f32,
 }
 
-impl UIVertex{
-    pub fn new(size:(i32,i32)) -> Self {
-        Self {
-            position: UIPosition {x: 0.0, y: 0.0, z:0.0},
-            color: UIColor {r: 0.0, g: 0.0, b: 0.0},
-            size: UISize {width:size.0 as f32,height:size.1 as f32},
-        }
-    }
-
+impl UIInstance{
     pub fn get_layout() -> wgpu::VertexBufferLayout<'static> {
 
-        const ATTR: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x2];
+        const ATTR: [wgpu::VertexAttribute; 7] = wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4, 2 => Float32x4, 3 => Float32x4, 4 => Float32x4, 5 => Float32x4, 6 => Float32];
 
-        wgpu::VertexBufferLayout { array_stride: std::mem::size_of::<UIVertex>() as u64, step_mode: wgpu::VertexStepMode::Vertex, attributes: &ATTR }
+        wgpu::VertexBufferLayout { array_stride: std::mem::size_of::<UIInstance>() as u64, step_mode: wgpu::VertexStepMode::Instance, attributes: &ATTR }
     }
 }
 
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct UIUniforms {
+    screen_size: UISize,
+    // uniform buffers are sized in multiples of 16 bytes
+    _padding: [f32; 2],
+}
+
+/// A run of shapes followed by a run of text, layers are drawn in order so
+/// translucent shapes composite over the text that came before them.
+#[derive(Copy, Clone, Debug)]
+struct UILayer {
+    first_instance: usize,
+    first_line: usize,
+    first_custom: usize,
+}
+
+const INITIAL_INSTANCE_CAPACITY: usize = 4096;
+
+/// Stands in for a missing scissor region, larger than any screen.
+const NO_CLIP: [f32; 4] = [-1.0e6, -1.0e6, 2.0e6, 2.0e6];
+
+/// Draws clay render commands with wgpu.
+///
+/// The renderer does not own a surface or a window, it is handed the render pass to draw
+/// into every frame. That pass needs a color attachment in the format the renderer was
+/// created with and a [`wgpu::TextureFormat::Depth32Float`] depth attachment cleared to 1.0,
+/// see [`crate::graphics::depth_texture::DepthTexture`].
+///
+/// Text is measured through the same instance that draws it, so hand it to clay with
+/// [`crate::ui::ui_text::measure_text`] before declaring a layout.
 pub struct UIState{
-    pub vertices: Vec<UIVertex>,
+    pub instances: Vec<UIInstance>,
     pub buffer: wgpu::Buffer,
-    pub number_of_vertices: usize,
+    instance_high_water_mark: usize,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
 
     render_pipeline: wgpu::RenderPipeline,
+    format: wgpu::TextureFormat,
+    pub images: UIImages,
+    pub custom_elements: UICustomElements,
 
-    pub font_system: FontSystem,
-    swash_cache: SwashCache,
+    pub ui_text: UIText,
+    pub selection: UISelection,
+    pub focus_ring: UIFocusRing,
     viewport: glyphon::Viewport,
     atlas: glyphon::TextAtlas,
-    text_renderer: glyphon::TextRenderer,
-    pub measurement_buffer: glyphon::Buffer,
+    text_renderers: Vec<glyphon::TextRenderer>,
     pub lines: Vec<TextLine>,
+    layers: Vec<UILayer>,
+    customs: Vec<CustomCommand>,
+    /// x, y, width, height applied to every instance pushed, see [`UIState::push_clip`]
+    clip: [f32; 4],
+    clip_stack: Vec<[f32; 4]>,
+    /// how much closer each render command of the frame is drawn than the one before it,
+    /// small enough for all of them to fit into the depth range
+    depth_step: f32,
 
     pub dpi_scale: f32,
 }
 
 impl UIState{
-    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue,  pixel_format:wgpu::TextureFormat, size:PhysicalSize<u32>) -> Self {
-        let (buffer, vertices) = make_ui_buffer(device, "ui triangle buffer", 10000, (size.width as i32, size.height as i32));
+    /// Creates a renderer for color targets of `pixel_format`, `resolution` is the initial
+    /// size in pixels of the target, every render call passes the current one.
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue,  pixel_format:wgpu::TextureFormat, resolution:(u32, u32)) -> Self {
+        let buffer = make_ui_buffer(device, "ui instance buffer", INITIAL_INSTANCE_CAPACITY);
+
+        let uniforms = UIUniforms {
+            screen_size: UISize { width: resolution.0 as f32, height: resolution.1 as f32 },
+            _padding: [0.0; 2],
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("ui uniform buffer"),
+            contents: bytemuck::bytes_of(&uniforms),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let uniform_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("ui uniform bind group layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("ui uniform bind group"),
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let images = UIImages::new(device);
 
         let mut ui_pipeline_builder = UIPipeline::new(pixel_format);
-        ui_pipeline_builder.add_buffer_layout(UIVertex::get_layout());
+        ui_pipeline_builder.add_buffer_layout(UIInstance::get_layout());
+        ui_pipeline_builder.add_bind_group_layout(images.bind_group_layout.clone());
+        ui_pipeline_builder.add_bind_group_layout(uniform_bind_group_layout);
         let render_pipeline = ui_pipeline_builder.build_pipeline(&device);
 
-        let mut font_system = FontSystem::new();
-        let swash_cache = SwashCache::new();
         let cache = Cache::new(&device);
         let viewport = Viewport::new(&device, &cache);
         let mut atlas = TextAtlas::new(&device, &queue, &cache, pixel_format);
-        let text_renderer = TextRenderer::new(
-            &mut atlas, &device, MultisampleState::default(),
-            Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less, // 1.
-                stencil: wgpu::StencilState::default(), // 2.
-                bias: wgpu::DepthBiasState::default(),
-            })
-        );
-        let measurement_buffer = Buffer::new(&mut font_system, Metrics::new(30.0, 42.0));
-
+        let text_renderer = make_text_renderer(&mut atlas, device);
         Self {
-            vertices,
+            instances: Vec::<UIInstance>::with_capacity(INITIAL_INSTANCE_CAPACITY),
             buffer,
-            number_of_vertices: 0,
+            instance_high_water_mark: 0,
+            uniform_buffer,
+            uniform_bind_group,
             render_pipeline,
-            
-            font_system,
-            swash_cache,
+            format: pixel_format,
+            images,
+            custom_elements: UICustomElements::new(),
+
+            ui_text: UIText::new(),
+            selection: UISelection::new(),
+            focus_ring: UIFocusRing::default(),
             viewport,
             atlas,
-            text_renderer,
-            measurement_buffer,
+            text_renderers: vec![text_renderer],
             lines: Vec::<TextLine>::new(),
+            layers: Vec::<UILayer>::new(),
+            customs: Vec::<CustomCommand>::new(),
+            clip: NO_CLIP,
+            clip_stack: Vec::new(),
+            depth_step: 0.0,
             dpi_scale: 1.0
         }
     }
 
-    pub fn render(&mut self, render_pass:&mut wgpu::RenderPass, queue: &wgpu::Queue){
-
-        render_pass.set_pipeline(&self.render_pipeline);
-
-        queue.write_buffer(
-            &self.buffer,
-            0,
-            bytemuck::cast_slice(&self.vertices.get(0..self.number_of_vertices).unwrap()),
-        );
+    /// Draws everything queued since the last call into `render_pass` and clears the queue.
+    /// [`UIState::render_clay`] calls this, it is only needed when drawing without clay.
+    pub fn render(&mut self, render_pass:&mut wgpu::RenderPass, device: &wgpu::Device, queue: &wgpu::Queue, resolution: (u32, u32)){
+        self.instance_high_water_mark = self.instance_high_water_mark.max(self.instances.len());
+
+        let required_size = (self.instances.len() * std::mem::size_of::<UIInstance>()) as u64;
+        if required_size > self.buffer.size() {
+            let capacity = self.instances.len().next_power_of_two();
+            log::warn!(
+                "ui instance buffer full, reallocating from {} to {} instances",
+                self.buffer.size() / std::mem::size_of::<UIInstance>() as u64,
+                capacity
+            );
+            self.buffer = make_ui_buffer(device, "ui instance buffer", capacity);
+        }
 
-        render_pass.set_vertex_buffer(0, self.buffer.slice(..));
-        render_pass.draw(0..self.number_of_vertices as u32, 0..1);
+        if !self.instances.is_empty() {
+            queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&self.instances));
+        }
 
-        self.number_of_vertices = 0;
-    }
+        let uniforms = UIUniforms {
+            screen_size: UISize { width: resolution.0 as f32, height: resolution.1 as f32 },
+            _padding: [0.0; 2],
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
 
-    fn render_text(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, render_pass:&mut wgpu::RenderPass, surface_config: &wgpu::SurfaceConfiguration) {
         self.atlas.trim();
 
         self.viewport.update(
             &queue,
             Resolution {
-                width: surface_config.width,
-                height: surface_config.height,
+                width: resolution.0,
+                height: resolution.1,
             },
         );
 
+        while self.text_renderers.len() < self.layers.len() {
+            self.text_renderers.push(make_text_renderer(&mut self.atlas, device));
+        }
+
+        for index in 0..self.layers.len() {
+            let layer = self.layers[index];
+            let (instance_end, line_end, custom_end) = match self.layers.get(index+1) {
+                Some(next) => (next.first_instance, next.first_line, next.first_custom),
+                None => (self.instances.len(), self.lines.len(), self.customs.len())
+            };
+
+            if instance_end > layer.first_instance {
+                self.bind_pipeline(render_pass);
+                // six vertices make the quad, the instance supplies everything else
+                render_pass.draw(0..6, layer.first_instance as u32..instance_end as u32);
+            }
+
+            if line_end > layer.first_line {
+                self.render_text(index, layer.first_line..line_end, device, queue, render_pass, resolution);
+            }
+
+            for custom in self.customs[layer.first_custom..custom_end].iter() {
+                self.custom_elements.draw(custom, resolution, device, queue, render_pass);
+            }
+            if custom_end > layer.first_custom {
+                // callbacks leave their own pipeline and bind groups behind
+                self.bind_pipeline(render_pass);
+            }
+        }
+
+        self.instances.clear();
+        self.lines.clear();
+        self.layers.clear();
+        self.customs.clear();
+        self.clip_stack.clear();
+        self.clip = NO_CLIP;
+    }
+
+    fn bind_pipeline(&self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.images.bind_group, &[]);
+        render_pass.set_bind_group(1, &self.uniform_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.buffer.slice(..));
+    }
+
+    fn render_text(&mut self, layer: usize, lines: Range<usize>, device: &wgpu::Device, queue: &wgpu::Queue, render_pass:&mut wgpu::RenderPass, resolution: (u32, u32)) {
         let mut areas = Vec::<TextArea>::new();
+        // the text of a layer follows all of its shapes, so the whole layer is drawn at the depth
+        // of its last line and the buffers stay the same wherever the text ends up
+        let mut depth: f32 = 1.0;
 
-        for text_line in self.lines.iter_mut() {
+        for text_line in self.lines[lines].iter() {
+            depth = depth.min(text_line.depth);
             areas.push(TextArea { 
                 buffer: &text_line.line, 
                 left: text_line.left, 
@@ -275,18 +421,18 @@ impl UIState{
                 bounds: match text_line.bounds {
                     Some((position,bounds)) => {
                         TextBounds { 
-                            left: position.x as i32, 
-                            top: position.y as i32, 
-                            right: (position.x + bounds.x) as i32, 
-                            bottom: (position.y + bounds.y) as i32
+                            left: position.x.floor() as i32, 
+                            top: position.y.floor() as i32, 
+                            right: (position.x + bounds.x).ceil() as i32, 
+                            bottom: (position.y + bounds.y).ceil() as i32
                         }
                     }
                     None => {
                         TextBounds { 
                             left: 0, 
                             top: 0, 
-                            right: surface_config.width as i32, 
-                            bottom: surface_config.height as i32
+                            right: resolution.0 as i32, 
+                            bottom: resolution.1 as i32
                         }
                     }
                 }, 
@@ -295,257 +441,294 @@ impl UIState{
             });
         }
 
-        self.text_renderer.prepare_with_depth(
+        let text_renderer = &mut self.text_renderers[layer];
+
+        text_renderer.prepare_with_depth(
             device,
             queue,
-            &mut self.font_system,
+            &mut self.ui_text.font_system,
             &mut self.atlas,
             &mut self.viewport,
             areas.into_iter(),
-            &mut self.swash_cache,
-            |metadata| { 
-                (metadata as f32) / 10000.0
-            }
+            &mut self.ui_text.swash_cache,
+            |_| depth,
         ).unwrap();
 
-        self.text_renderer.render(&self.atlas, &self.viewport, render_pass).unwrap();
-
-        self.lines.clear();
+        text_renderer.render(&self.atlas, &self.viewport, render_pass).unwrap();
     }
 
-    #[allow(dead_code)]
-    pub fn measure_text(&mut self, text: &str, font_size:f32, line_height:f32) -> Dimensions {
+    /// Shapes that come after text start a new layer, so they are drawn over that text.
+    fn begin_shapes(&mut self) {
+        if self.lines.len() > self.layers.last().unwrap().first_line {
+            self.layers.push(UILayer {
+                first_instance: self.instances.len(),
+                first_line: self.lines.len(),
+                first_custom: self.customs.len(),
+            });
+        }
+    }
 
-        self.measurement_buffer.set_metrics_and_size(&mut self.font_system, Metrics{
-            font_size: font_size * self.dpi_scale, 
-            line_height: match line_height {
-                0.0 => (font_size * 1.5) * self.dpi_scale,
-                _ => line_height * self.dpi_scale
-            }
-        }, None, None);
-        self.measurement_buffer.set_text(&mut self.font_system, text, Attrs::new().family(Family::SansSerif), Shaping::Advanced);
-        self.measurement_buffer.shape_until_scroll(&mut self.font_system, false);
+    /// Queues the custom element drawn after everything before it, anything that follows
+    /// goes into a new layer so it is drawn over the element.
+    fn custom(&mut self, element: CustomElementId, position: UIPosition, size: UIPosition) {
+        let bounding_box = [position.x, position.y, size.x, size.y];
+        self.customs.push(CustomCommand {
+            element,
+            bounding_box,
+            // the scissor region alone is the whole target for elements outside scroll containers
+            clip: intersect_rects(self.clip, bounding_box),
+            depth: position.z,
+        });
 
-        (self.measurement_buffer.layout_runs().next().unwrap().line_w, self.measurement_buffer.metrics().line_height).into()
+        self.layers.push(UILayer {
+            first_instance: self.instances.len(),
+            first_line: self.lines.len(),
+            first_custom: self.customs.len(),
+        });
     }
 
-    pub fn resize(&mut self, size:(i32,i32)){
-        for vertex in self.vertices.as_mut_slice() {
-            vertex.size.width = size.0 as f32;
-            vertex.size.height = size.1 as f32;
-        }
+    /// The color format this renderer draws in.
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.format
     }
 
-    pub fn triangle(&mut self, positions: &[UIPosition; 3], color: UIColor){
-        match self.vertices.get_mut(self.number_of_vertices..self.number_of_vertices+3) {
-            None => return,
-            Some(vertices) => {
-                for (vertex, position) in vertices.iter_mut().zip(positions.iter()) {
-                    vertex.position = *position;
-                    vertex.color = color;
-                    self.number_of_vertices += 1;
-                }
-            }
-        }
+    /// The most instances used by a single frame so far.
+    pub fn instance_high_water_mark(&self) -> usize {
+        self.instance_high_water_mark
     }
 
-    pub fn quad(&mut self, positions: &[UIPosition; 4], color: UIColor){
-        match self.vertices.get_mut(self.number_of_vertices..self.number_of_vertices+6) {
-            None => return,
-            Some(vertices) => {
-                vertices.get_mut(0).unwrap().position = positions[0];
-                vertices.get_mut(0).unwrap().color = color;
+    /// Restricts everything drawn until the matching [`UIState::pop_clip`] to the given region,
+    /// intersected with the region already in effect.
+    pub fn push_clip(&mut self, position: UIPosition, size: UIPosition) {
+        self.clip_stack.push(self.clip);
+        self.clip = intersect_rects(self.clip, [position.x, position.y, size.x, size.y]);
+    }
 
-                vertices.get_mut(1).unwrap().position = positions[1];
-                vertices.get_mut(1).unwrap().color = color;
+    pub fn pop_clip(&mut self) {
+        self.clip = self.clip_stack.pop().unwrap_or(NO_CLIP);
+    }
 
-                vertices.get_mut(2).unwrap().position = positions[2];
-                vertices.get_mut(2).unwrap().color = color;
+    /// The clip region as glyphon text bounds, `None` when nothing is clipped.
+    fn clip_bounds(&self) -> Option<(UIPosition, UIPosition)> {
+        match self.clip_stack.is_empty() {
+            true => None,
+            false => Some((
+                UIPosition { x: self.clip[0], y: self.clip[1], z: 0.0 },
+                UIPosition { x: self.clip[2], y: self.clip[3], z: 0.0 },
+            ))
+        }
+    }
 
-                vertices.get_mut(3).unwrap().position = positions[0];
-                vertices.get_mut(3).unwrap().color = color;
+    fn rounded_box(&mut self, position: UIPosition, size:UIPosition, color: UIColor, radii:UICornerRadii, border: [f32; 4], uv: [f32; 4]){
+        self.instances.push(UIInstance {
+            rect: [position.x, position.y, size.x, size.y],
+            radii: [radii.top_left, radii.top_right, radii.bottom_right, radii.bottom_left],
+            border,
+            color,
+            uv,
+            clip: self.clip,
+            depth: position.z,
+        });
+    }
 
-                vertices.get_mut(4).unwrap().position = positions[2];
-                vertices.get_mut(4).unwrap().color = color;
+    pub fn rectangle(&mut self, position: UIPosition, size:UIPosition, thickness:UIBorderThickness, color: UIColor, radii:UICornerRadii){
+        self.rounded_box(position, size, color, radii, [thickness.top, thickness.right, thickness.bottom, thickness.left], [0.0; 4]);
+    }
 
-                vertices.get_mut(5).unwrap().position = positions[3];
-                vertices.get_mut(5).unwrap().color = color;
+    pub fn filled_rectangle(&mut self, position:UIPosition, size:UIPosition, color: UIColor, radii:UICornerRadii){
+        self.rounded_box(position, size, color, radii, [0.0; 4], [0.0; 4]);
+    }
 
-                self.number_of_vertices += 6;
-            }
+    /// Draws `image`, tinted by `color`. Nothing is drawn if the image was removed.
+    pub fn image(&mut self, position:UIPosition, size:UIPosition, image: ImageId, color: UIColor, radii:UICornerRadii, queue: &wgpu::Queue){
+        if let Some(uv) = self.images.prepare(queue, image) {
+            self.rounded_box(position, size, color, radii, [0.0; 4], uv);
         }
     }
 
-    pub fn line(&mut self, position: UIPosition, length:f32, angle:f32, thickness:f32, color: UIColor){
-        let mut line: [UIPosition;4] = [UIPosition::new(); 4];
-
-        line[0].add_y(-(thickness/2.0));
-        line[1].add_y(thickness/2.0);
-        line[2].add_x(length).add_y(thickness/2.0);
-        line[3].add_x(length).add_y(-(thickness/2.0));
+    /// Text is broken into lines by the wrap of `shaping`, the way clay measured it, and with
+    /// [`TextWrap::Words`] lines wider than its wrap width wrap between words. Its alignment
+    /// places those lines within the wrap width.
+    /// Draws text with the letter spacing of `shaping` between its glyphs, and the underlines and
+    /// strikethroughs of its font and rich text spans as rectangles over it.
+    pub fn text(&mut self, text: &str, shaping: TextShaping, position: UIPosition, bounds:Option<(UIPosition, UIPosition)>, color:cosmic_text::Color){
+        let line = self.ui_text.shape_with(text, shaping);
+        self.span_backgrounds(text, &line, position, shaping);
+        self.shaped_text(text, line, shaping, position, bounds, color);
+    }
 
-        for point in line.iter_mut() {
-            point.rotate(angle);
-            *point = *point + position;
+    /// Draws `text` as shaped into `line` by [`UIText::shape_with`].
+    fn shaped_text(&mut self, text: &str, line: Rc<glyphon::Buffer>, shaping: TextShaping, position: UIPosition, bounds:Option<(UIPosition, UIPosition)>, color:cosmic_text::Color){
+        let (align, letter_spacing) = (shaping.align, shaping.letter_spacing);
+        if letter_spacing == 0.0 {
+            self.lines.push(TextLine{
+                line: line.clone(),
+                left: position.x,
+                top: position.y,
+                depth: position.z,
+                color,
+                bounds,
+            });
+        }
+        else {
+            // glyphon draws buffers as they were laid out, so every cluster is drawn from a
+            // buffer of its own, shaped once and cached like any other text, where the whole
+            // text placed it plus its offset
+            let mut last_cluster = None;
+            for spaced in spaced_glyphs(&line, align, letter_spacing) {
+                // glyphs of one cluster, like a base and its marks, come from one buffer
+                if last_cluster == Some(spaced.range.clone()) {
+                    continue;
+                }
+                last_cluster = Some(spaced.range.clone());
+
+                let cluster = self.ui_text.shape_with(&text[spaced.range.clone()], TextShaping::new(shaping.font_id, shaping.metrics).wrap(TextWrap::None, None));
+                let baseline = cluster.layout_runs().next().map_or(0.0, |run| run.line_y);
+                self.lines.push(TextLine{
+                    line: cluster,
+                    left: position.x + spaced.glyph.x + spaced.offset,
+                    top: position.y + spaced.line_y - baseline,
+                    depth: position.z,
+                    color,
+                    bounds,
+                });
+            }
         }
 
-        self.quad(&line, color);
-    }
-
-    pub fn arc(&mut self, origin:UIPosition, radius:f32, degree_begin:f32, degree_end:f32, thickness:f32, color: UIColor) {
-        let arc_length = (degree_end-degree_begin).abs();
-        let number_of_segments = 10.0;
-        let arc_segment_length = arc_length / number_of_segments; // 10 = number of segments
-        let arc_segment_distance = (2.0*std::f32::consts::PI*radius) * (arc_segment_length/360.0);
-    
-        let mut arc_point = UIPosition {x:0.0, y:0.0, z:origin.z };
-    
-        for i in 0..number_of_segments as i32{
-            arc_point.x = radius;
-            arc_point.y = 0.0;
-            arc_point.rotate(degree_begin+(arc_segment_length*(i as f32)));
-
-            arc_point = arc_point + origin;
-    
-            self.line(
-                arc_point,
-                arc_segment_distance,
-                degree_begin+90.0+(arc_segment_length*i as f32)+(arc_segment_length/2.0),
-                thickness,
-                color
+        let decorations = decoration_rects(&line, align, letter_spacing);
+        if !decorations.is_empty() {
+            self.begin_shapes();
+        }
+        for decoration in decorations {
+            self.filled_rectangle(
+                UIPosition { x: position.x + decoration.x, y: position.y + decoration.y, z: position.z },
+                UIPosition { x: decoration.width, y: decoration.height, z: position.z },
+                linear_color(decoration.color.unwrap_or(color)),
+                UICornerRadii { top_left: 0.0, top_right: 0.0, bottom_left: 0.0, bottom_right: 0.0 },
             );
         }
     }
 
-    pub fn filled_arc(&mut self, origin:UIPosition, radius:f32, degree_begin:f32, degree_end:f32, color: UIColor) {
-        let arc_length = (degree_end-degree_begin).abs();
-        let number_of_segments = 10.0;
-        let arc_segment_length = arc_length / number_of_segments; // 10 = number of segments
-    
-        let mut current_point = UIPosition {x:0.0, y:0.0, z:origin.z};
-        let mut next_point = UIPosition {x:0.0, y:0.0, z:origin.z};
-    
-        for i in 0..number_of_segments as i32{
-            current_point.x = radius;
-            current_point.y = 0.0;
-            current_point.rotate(degree_begin+(arc_segment_length*(i as f32+1.0)));
-    
-            next_point.x = radius;
-            next_point.y = 0.0;
-            next_point.rotate(degree_begin+(arc_segment_length*(i as f32+0.0)));
-    
-            self.triangle(
-                &[
-                    current_point + origin,
-                    origin, 
-                    next_point + origin
-                ],
-                color,
-            );
+    /// Draws the backgrounds of the rich text spans in `text` beneath it, under the selection.
+    fn span_backgrounds(&mut self, text: &str, buffer: &glyphon::Buffer, position: UIPosition, shaping: TextShaping) {
+        let backgrounds = self.ui_text.rich_text.spans(text).into_iter()
+            .filter_map(|span| span.style.background.map(|color| (span.range, color)))
+            .collect::<Vec<_>>();
+        if backgrounds.is_empty() {
+            return;
+        }
+
+        self.begin_shapes();
+        // behind the text, like the selection highlight
+        let depth = position.z + self.depth_step / 2.0;
+        for (range, color) in backgrounds {
+            for (x, y, width, height) in range_rects(buffer, shaping.align, shaping.letter_spacing, range) {
+                self.filled_rectangle(
+                    UIPosition { x: position.x + x, y: position.y + y, z: depth },
+                    UIPosition { x: width, y: height, z: depth },
+                    linear_color(color),
+                    UICornerRadii { top_left: 0.0, top_right: 0.0, bottom_left: 0.0, bottom_right: 0.0 },
+                );
+            }
         }
     }
 
-    pub fn rectangle(&mut self, mut position: UIPosition, size:UIPosition, thickness:UIBorderThickness, color: UIColor, radii:UICornerRadii){
-        self.arc(position+radii.top_left, radii.top_left, 90.0, 180.0, thickness.top, color);
-        self.arc(position.with_x(size.x-radii.top_right).with_y(radii.top_right), radii.top_right, 0.0, 90.0, thickness.top, color);
-        self.arc(position.with_y(size.y-radii.bottom_left).with_x(radii.bottom_left), radii.bottom_left, 180.0, 270.0, thickness.bottom, color);
-        self.arc(position+(size-radii.bottom_right), radii.bottom_right, 270.0, 360.0, thickness.bottom, color);
+    /// Hands text drawn for render command `element_id` from `buffer` to the selection, and
+    /// draws the part of it that is selected beneath it.
+    fn selectable_text(&mut self, element_id: u32, text: &str, buffer: Rc<glyphon::Buffer>, position: UIPosition, size: UIPosition, shaping: TextShaping) {
+        let highlights = self.selection.add_line(element_id, text, (position.x, position.y), (size.x, size.y), buffer, shaping);
+        if highlights.is_empty() {
+            return;
+        }
 
-        self.line(position.with_x(radii.top_left), size.x-(radii.top_left+radii.top_right), 0.0, thickness.top, color);
-        self.line(position.with_y(radii.top_left), size.y-(radii.top_left+radii.bottom_left), 270.0, thickness.left, color);
-        self.line(position.with_x(radii.bottom_left).with_y(size.y), size.x-(radii.bottom_left+radii.bottom_right), 0.0, thickness.bottom, color);
-        self.line(position.with_x(size.x).with_y(radii.top_right), size.y-(radii.top_right+radii.bottom_right), 270.0, thickness.right, color);
+        self.begin_shapes();
+        let color = self.selection.highlight_color;
+        // behind the text, which is depth tested against what is drawn beneath it
+        let depth = position.z + self.depth_step / 2.0;
+        for (x, y, width, height) in highlights {
+            self.filled_rectangle(
+                UIPosition { x, y, z: depth },
+                UIPosition { x: width, y: height, z: depth },
+                color,
+                UICornerRadii { top_left: 0.0, top_right: 0.0, bottom_left: 0.0, bottom_right: 0.0 },
+            );
+        }
     }
 
-    #[allow(dead_code)]
-    pub fn filled_rectangle(&mut self, mut position:UIPosition, size:UIPosition, color: UIColor, radii:UICornerRadii){
-
-        self.filled_arc(position+radii.top_left, radii.top_left, 90.0, 180.0, color);
-        self.filled_arc(position.with_x(size.x-radii.top_right).with_y(radii.top_right), radii.top_right, 0.0, 90.0, color);
-        self.filled_arc(position.with_y(size.y-radii.bottom_left).with_x(radii.bottom_left), radii.bottom_left, 180.0, 270.0, color);
-        self.filled_arc(position+(size-radii.top_right), radii.bottom_right, 270.0, 360.0, color);
+    /// Draws the focus ring around an element, over everything else and clipped the way
+    /// the element was.
+    fn draw_focus_ring(&mut self, position: UIPosition, size: UIPosition, radii: UICornerRadii, clip: [f32; 4], depth: f32) {
+        let ring = self.focus_ring;
+        let grow = ring.offset + ring.width;
+        // rounded corners stay rounded around the gap, square ones stay square
+        let outer = |radius: f32| match radius > 0.0 {
+            true => radius + grow,
+            false => 0.0,
+        };
 
-        // top
-        self.quad(
-            &[
-                position.with_x(radii.top_left),
-                position+radii.top_left,
-                position.with_x(size.x-radii.top_right).with_y(radii.top_right),
-                position.with_x(size.x-radii.top_right)
-            ],
-            color
-        );
-        // bottom
-        self.quad(
-            &[
-                position.with_x(radii.bottom_left).with_y(size.y-radii.bottom_left),
-                position.with_x(radii.bottom_left).with_y(size.y),
-                position.with_x(size.x-radii.bottom_right).with_y(size.y),
-                position.with_x(size.x-radii.bottom_right).with_y(size.y-radii.bottom_right)
-            ],
-            color
-        );
-        // left
-        self.quad(
-            &[
-                position.with_y(radii.top_left),
-                position.with_y(size.y-radii.bottom_left),
-                position.with_x(radii.bottom_left).with_y(size.y-radii.bottom_left),
-                position+radii.top_left
-            ],
-            color
-        );
-        // right
-        self.quad(
-            &[
-                position.with_x(size.x-radii.top_right),
-                position.with_x(size.x-radii.bottom_right).with_y(size.y-radii.bottom_right),
-                position.with_x(size.x).with_y(size.y-radii.bottom_right),
-                position.with_x(size.x).with_y(radii.top_right)
-            ],
-            color
-        );
-        // center
-        self.quad(
-            &[
-                position+radii.top_left,
-                position.with_x(radii.bottom_left).with_y(size.y-radii.bottom_left),
-                position.with_x(size.x-radii.bottom_right).with_y(size.y-radii.bottom_right),
-                position.with_x(size.x-radii.top_right).with_y(radii.top_right)
-            ],
-            color
+        self.begin_shapes();
+        let element_clip = std::mem::replace(&mut self.clip, clip);
+        self.rectangle(
+            UIPosition { x: position.x - grow, y: position.y - grow, z: depth },
+            UIPosition { x: size.x + grow * 2.0, y: size.y + grow * 2.0, z: depth },
+            UIBorderThickness { top: ring.width, left: ring.width, bottom: ring.width, right: ring.width },
+            ring.color,
+            UICornerRadii {
+                top_left: outer(radii.top_left),
+                top_right: outer(radii.top_right),
+                bottom_left: outer(radii.bottom_left),
+                bottom_right: outer(radii.bottom_right)
+            },
         );
+        self.clip = element_clip;
     }
 
-    pub fn text(&mut self, text: &str, font_size:f32, line_height:f32, position: UIPosition, bounds:Option<(UIPosition, UIPosition)>, color:cosmic_text::Color, draw_order:f32){
-        let mut line = Buffer::new(&mut self.font_system, Metrics::new(font_size,line_height));
-
-        line.set_text(&mut self.font_system, text, Attrs::new().family(Family::SansSerif).metadata((draw_order*10000.0) as usize), Shaping::Advanced);
-        
-        line.shape_until_scroll(&mut self.font_system, false);
-
-        self.lines.push(TextLine{
-            line,
-            left: position.x,
-            top: position.y,
-            color,
-            bounds,
+    /// Draws one frame of clay render commands into `render_pass`, whose color target is
+    /// `resolution` pixels in size.
+    pub fn render_clay<'b>(&mut self, commands: impl IntoIterator<Item = RenderCommand<'b>>, render_pass:&mut wgpu::RenderPass, device: &wgpu::Device, queue: &wgpu::Queue, resolution: (u32, u32)) {
+        let commands = commands.into_iter().collect::<Vec<_>>();
+        // the depth attachment is cleared to 1.0, the focus ring after the last command stays above 0.0
+        self.depth_step = 1.0 / (commands.len() + 2) as f32;
+        let mut depth: f32 = 1.0 - self.depth_step;
+
+        self.selection.begin_frame();
+        self.layers.push(UILayer {
+            first_instance: self.instances.len(),
+            first_line: self.lines.len(),
+            first_custom: self.customs.len(),
         });
-    }
 
-    pub fn render_clay<'b>(&mut self, commands: impl IntoIterator<Item = RenderCommand<'b>>, render_pass:&mut wgpu::RenderPass, device: &wgpu::Device, queue: &wgpu::Queue, surface_config: &wgpu::SurfaceConfiguration) {
-        let mut scissor_position = UIPosition::new();
-        let mut scissor_bounds = UIPosition::new();
-        let mut scissor_active = false;
-        let mut depth: f32 = 0.1;
+        // position, size, corner radii and clip of the focused element
+        let mut focused = None;
 
         for command in commands {
+            if focused.is_none() && self.focus_ring.element_id == Some(command.id) {
+                let radii = match &command.config {
+                    clay_layout::render_commands::RenderCommandConfig::Rectangle(r) => Some(&r.corner_radii),
+                    clay_layout::render_commands::RenderCommandConfig::Border(b) => Some(&b.corner_radii),
+                    clay_layout::render_commands::RenderCommandConfig::Image(i) => Some(&i.corner_radii),
+                    _ => None,
+                };
+                focused = radii.map(|radii| (
+                    UIPosition { x: command.bounding_box.x, y: command.bounding_box.y, z: 0.0 },
+                    UIPosition { x: command.bounding_box.width, y: command.bounding_box.height, z: 0.0 },
+                    UICornerRadii {
+                        top_left: radii.top_left,
+                        top_right: radii.top_right,
+                        bottom_left: radii.bottom_left,
+                        bottom_right: radii.bottom_right
+                    },
+                    self.clip,
+                ));
+            }
+
             match command.config {
                 clay_layout::render_commands::RenderCommandConfig::Rectangle(r) => {
+                    self.begin_shapes();
                     self.filled_rectangle(
                         UIPosition { x: command.bounding_box.x, y: command.bounding_box.y, z: depth as f32 }, 
                         UIPosition { x: command.bounding_box.width, y: command.bounding_box.height, z: depth as f32 }, 
-                        UIColor{ r:r.color.r/255.0, g:r.color.g/255.0, b:r.color.b/255.0 },
+                        UIColor{ r:r.color.r/255.0, g:r.color.g/255.0, b:r.color.b/255.0, a:r.color.a/255.0 },
                         UICornerRadii{
                             top_left: r.corner_radii.top_left,
                             top_right: r.corner_radii.top_right,
@@ -555,6 +738,7 @@ impl UIState{
                     );
                 }
                 clay_layout::render_commands::RenderCommandConfig::Border(b) => {
+                    self.begin_shapes();
                     self.rectangle(
                         UIPosition { x: command.bounding_box.x, y: command.bounding_box.y, z: depth as f32 }, 
                         UIPosition { x: command.bounding_box.width, y: command.bounding_box.height, z: depth as f32 },
@@ -567,7 +751,8 @@ impl UIState{
                         UIColor{
                             r: b.color.r/255.0,
                             g: b.color.g/255.0,
-                            b: b.color.b/255.0
+                            b: b.color.b/255.0,
+                            a: b.color.a/255.0
                         },
                         UICornerRadii { 
                             top_left: (b.corner_radii.top_left), 
@@ -577,61 +762,190 @@ impl UIState{
                         }
                     );
                 }
+                clay_layout::render_commands::RenderCommandConfig::Image(i) => {
+                    if let Some(image) = ImageId::from_image_data(i.image_data) {
+                        self.begin_shapes();
+                        self.image(
+                            UIPosition { x: command.bounding_box.x, y: command.bounding_box.y, z: depth as f32 },
+                            UIPosition { x: command.bounding_box.width, y: command.bounding_box.height, z: depth as f32 },
+                            image,
+                            // clay leaves the background color empty when no tint was asked for
+                            match i.background_color.a {
+                                0.0 => UIColor{ r:1.0, g:1.0, b:1.0, a:1.0 },
+                                _ => UIColor{ r:i.background_color.r/255.0, g:i.background_color.g/255.0, b:i.background_color.b/255.0, a:i.background_color.a/255.0 }
+                            },
+                            UICornerRadii{
+                                top_left: i.corner_radii.top_left,
+                                top_right: i.corner_radii.top_right,
+                                bottom_left: i.corner_radii.bottom_left,
+                                bottom_right: i.corner_radii.bottom_right
+                            },
+                            queue
+                        );
+                    }
+                }
                 clay_layout::render_commands::RenderCommandConfig::Text(text) => {
-                    self.text(
-                        text.text, 
-                        (text.font_size as f32) * self.dpi_scale, 
-                        match text.line_height {
-                            0 => {
-                                (text.font_size as f32) * 1.5 * self.dpi_scale
-                            }
-                            _ => (text.line_height as f32) * self.dpi_scale
-                        }, 
-                        UIPosition {x:command.bounding_box.x,y:command.bounding_box.y, z: depth as f32},
-                        match scissor_active {
-                            true => Some((scissor_position.clone(), scissor_bounds.clone())),
-                            false => None
-                        },
-                        Color::rgb(text.color.r as u8, text.color.g as u8, text.color.b as u8),
-                        depth
+                    let font_size = (text.font_size as f32) * self.dpi_scale;
+                    let line_height = match text.line_height {
+                        0 => {
+                            (text.font_size as f32) * 1.5 * self.dpi_scale
+                        }
+                        _ => (text.line_height as f32) * self.dpi_scale
+                    };
+                    let position = UIPosition {x:command.bounding_box.x,y:command.bounding_box.y, z: depth as f32};
+                    let shaping = TextShaping::new(text.font_id, glyphon::Metrics::new(font_size, line_height))
+                        .wrap(text.wrap_mode.into(), Some(text_wrap_width(command.bounding_box.width)))
+                        .align(text.alignment.into())
+                        .letter_spacing((text.letter_spacing as f32) * self.dpi_scale);
+                    // shaped once, the selection hit tests the glyphs exactly where they are drawn
+                    let buffer = self.ui_text.shape_with(text.text, shaping);
+
+                    self.span_backgrounds(text.text, &buffer, position, shaping);
+                    self.selectable_text(
+                        command.id,
+                        text.text,
+                        buffer.clone(),
+                        position,
+                        UIPosition { x: command.bounding_box.width, y: command.bounding_box.height, z: depth },
+                        shaping,
+                    );
+                    self.shaped_text(
+                        text.text,
+                        buffer,
+                        shaping,
+                        position,
+                        self.clip_bounds(),
+                        Color::rgba(text.color.r as u8, text.color.g as u8, text.color.b as u8, text.color.a as u8),
                     );
                 }
+                clay_layout::render_commands::RenderCommandConfig::Custom(c) => {
+                    if let Some(element) = CustomElementId::from_custom_data(c.custom_data) {
+                        self.custom(
+                            element,
+                            UIPosition { x: command.bounding_box.x, y: command.bounding_box.y, z: depth },
+                            UIPosition { x: command.bounding_box.width, y: command.bounding_box.height, z: depth },
+                        );
+                    }
+                }
                 clay_layout::render_commands::RenderCommandConfig::ScissorStart() => {
-                    scissor_position.x = command.bounding_box.x;
-                    scissor_position.y = command.bounding_box.y;
-                    scissor_bounds.x = command.bounding_box.width;
-                    scissor_bounds.y = command.bounding_box.height;
-                    scissor_active = true;
+                    self.push_clip(
+                        UIPosition { x: command.bounding_box.x, y: command.bounding_box.y, z: depth },
+                        UIPosition { x: command.bounding_box.width, y: command.bounding_box.height, z: depth },
+                    );
                 }
                 clay_layout::render_commands::RenderCommandConfig::ScissorEnd() => {
-                    scissor_active = false;
+                    self.pop_clip();
                 }
                 _ => {}
             }
-            depth -= 0.0001;
+            depth -= self.depth_step;
         }
 
-        if self.number_of_vertices > 0 {
-            self.render(render_pass, queue);
-        }
-        if self.lines.len() > 0 {
-            self.render_text(device, queue, render_pass, surface_config);
+        if let Some((position, size, radii, clip)) = focused {
+            self.draw_focus_ring(position, size, radii, clip, depth);
         }
-        
+
+        self.render(render_pass, device, queue, resolution);
+        self.images.end_frame();
+        self.ui_text.rich_text.clear();
+    }
+
+    /// Records a render pass that draws one frame of clay render commands into `target`,
+    /// cleared to `clear_color` first or drawn over its contents when that is `None`.
+    ///
+    /// Instance data is uploaded through the queue, so submit `encoder` before rendering
+    /// with the same renderer again, another target or the window included.
+    pub fn render_clay_to_target<'b>(&mut self, commands: impl IntoIterator<Item = RenderCommand<'b>>, target: &RenderTarget, clear_color: Option<wgpu::Color>, encoder: &mut wgpu::CommandEncoder, device: &wgpu::Device, queue: &wgpu::Queue) {
+        assert_eq!(
+            target.format(), self.format,
+            "render target format does not match the format the renderer was created with"
+        );
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("ui render target pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target.view(),
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: match clear_color {
+                        Some(color) => wgpu::LoadOp::Clear(color),
+                        None => wgpu::LoadOp::Load,
+                    },
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: target.depth_view(),
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None
+        });
+
+        self.render_clay(commands, &mut render_pass, device, queue, target.size());
     }
 }
 
-fn make_ui_buffer(device: &wgpu::Device, label: &str, number_of_triangles: usize, size:(i32,i32)) -> (wgpu::Buffer, Vec<UIVertex>) {
-    
-    let vertices: Vec<UIVertex> = vec![UIVertex::new(size);number_of_triangles*3];
+impl MeasureText for UIState {
+    fn measure_text(&mut self, text: &str, font_id: u16, font_size:f32, line_height:f32, letter_spacing:f32, wrap: TextWrap) -> Dimensions {
+        self.ui_text.measure(text, font_id, UIText::metrics(font_size, line_height, self.dpi_scale), letter_spacing * self.dpi_scale, wrap)
+    }
+}
 
-    let buffer_desctriptor = wgpu::util::BufferInitDescriptor {
-        label: Some(label),
-        contents: bytemuck::cast_slice(&vertices),
-        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST
-    };
+/// Clay sizes text elements to the width it measured, so text only wraps again when clay
+/// gave it less room than that. The slack keeps rounding from breaking a line that fits.
+pub fn text_wrap_width(bounding_box_width: f32) -> f32 {
+    bounding_box_width.ceil() + 1.0
+}
+
+/// The overlap of two x, y, width, height rectangles, empty when they don't overlap.
+fn intersect_rects(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    let left = a[0].max(b[0]);
+    let top = a[1].max(b[1]);
+    let right = (a[0] + a[2]).min(b[0] + b[2]);
+    let bottom = (a[1] + a[3]).min(b[1] + b[3]);
+    [left, top, (right - left).max(0.0), (bottom - top).max(0.0)]
+}
+
+/// Shapes are written as linear colors, text colors are srgb.
+fn linear_color(color: cosmic_text::Color) -> UIColor {
+    UIColor {
+        r: srgb_to_linear(color.r() as f32 / 255.0),
+        g: srgb_to_linear(color.g() as f32 / 255.0),
+        b: srgb_to_linear(color.b() as f32 / 255.0),
+        a: color.a() as f32 / 255.0,
+    }
+}
+
+pub(crate) fn srgb_to_linear(value: f32) -> f32 {
+    match value <= 0.04045 {
+        true => value / 12.92,
+        false => ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn make_text_renderer(atlas: &mut TextAtlas, device: &wgpu::Device) -> TextRenderer {
+    TextRenderer::new(
+        atlas, device, MultisampleState::default(),
+        Some(wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less, // 1.
+            stencil: wgpu::StencilState::default(), // 2.
+            bias: wgpu::DepthBiasState::default(),
+        })
+    )
+}
 
-    let buffer = device.create_buffer_init(&buffer_desctriptor);
-    
-    (buffer, vertices)
+fn make_ui_buffer(device: &wgpu::Device, label: &str, number_of_instances: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: (number_of_instances * std::mem::size_of::<UIInstance>()) as u64,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
 }
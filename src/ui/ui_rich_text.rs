@@ -0,0 +1,152 @@
+use std::ops::Range;
+
+use glyphon::Color;
+
+/// How a span of rich text differs from the text element it is declared in.
+/// Anything left unset is taken from the element's own config.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SpanStyle {
+    pub font_id: Option<u16>,
+    pub color: Option<Color>,
+    /// drawn behind the glyphs of the span, a rectangle for each line it is on
+    pub background: Option<Color>,
+}
+
+impl SpanStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draws the span in the face registered for `font_id`, with its weight, style and decoration.
+    pub fn font(mut self, font_id: u16) -> Self {
+        self.font_id = Some(font_id);
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+}
+
+/// A byte range of a rich text paragraph and its style.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextSpan {
+    pub range: Range<usize>,
+    pub style: SpanStyle,
+}
+
+/// A paragraph made of spans in faces and colors of their own, like bold words, inline code
+/// or links, that is measured and drawn as one clay text element.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RichText {
+    text: String,
+    spans: Vec<TextSpan>,
+}
+
+impl RichText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends text in the style of the text element.
+    pub fn plain(mut self, text: &str) -> Self {
+        self.text.push_str(text);
+        self
+    }
+
+    /// Appends text in `style`.
+    pub fn span(mut self, text: &str, style: SpanStyle) -> Self {
+        let start = self.text.len();
+        self.text.push_str(text);
+        self.spans.push(TextSpan {
+            range: start..self.text.len(),
+            style,
+        });
+        self
+    }
+
+    /// The text of all spans, to declare the clay text element with.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+}
+
+struct DeclaredText {
+    start: usize,
+    end: usize,
+    spans: Vec<TextSpan>,
+}
+
+/// The rich text declared for the frame being laid out.
+///
+/// Clay only hands slices of the declared text to measurement and rendering, a word at a time
+/// while measuring and a wrapped line at a time while drawing. Those slices still point into
+/// the text of the [`RichText`], so its spans are found again by address. The text has to stay
+/// where it is until the frame is rendered, which clay already requires of all text.
+#[derive(Default)]
+pub struct UIRichText {
+    declared: Vec<DeclaredText>,
+}
+
+impl UIRichText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares the spans of `text` for this frame, before its text element is declared
+    /// with `clay.text(text.text(), config)`.
+    pub fn declare(&mut self, text: &RichText) {
+        if text.spans.is_empty() {
+            return;
+        }
+
+        let start = text.text.as_ptr() as usize;
+        self.declared.push(DeclaredText {
+            start,
+            end: start + text.text.len(),
+            spans: text.spans.clone(),
+        });
+    }
+
+    /// Forgets the text declared for the frame, the renderers call this once it is drawn.
+    pub fn clear(&mut self) {
+        self.declared.clear();
+    }
+
+    /// The spans overlapping `text`, with ranges relative to it. Text that is not part of any
+    /// declared rich text has none.
+    pub fn spans(&self, text: &str) -> Vec<TextSpan> {
+        if text.is_empty() {
+            return Vec::new();
+        }
+
+        // addresses are only compared, never read from
+        let start = text.as_ptr() as usize;
+        let end = start + text.len();
+        let Some(declared) = self.declared.iter().find(|declared| declared.start <= start && end <= declared.end) else {
+            return Vec::new();
+        };
+
+        let offset = start - declared.start;
+        declared.spans.iter()
+            .filter_map(|span| {
+                let span_start = span.range.start.max(offset);
+                let span_end = span.range.end.min(offset + text.len());
+                (span_start < span_end).then(|| TextSpan {
+                    range: span_start - offset..span_end - offset,
+                    style: span.style,
+                })
+            })
+            .collect()
+    }
+}
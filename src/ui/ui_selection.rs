@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use glyphon::Buffer;
+
+use crate::ui::ui_clipboard::Clipboard;
+use crate::ui::ui_renderer::UIColor;
+use crate::ui::ui_text::{range_rects, spaced_glyphs, TextAlign, TextShaping};
+
+/// Two clicks closer together than this, in time and in pixels, select a word.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+const DOUBLE_CLICK_DISTANCE: f32 = 4.0;
+
+/// A line of text drawn in the last frame, where clay put it and how it was shaped.
+pub struct SelectableLine {
+    /// the id of the clay render command that drew the line
+    pub id: u32,
+    pub text: String,
+    pub position: (f32, f32),
+    pub size: (f32, f32),
+    pub buffer: Rc<Buffer>,
+    /// the alignment and letter spacing the buffer was drawn with
+    pub align: TextAlign,
+    pub letter_spacing: f32,
+}
+
+impl SelectableLine {
+    fn contains(&self, position: (f32, f32)) -> bool {
+        position.0 >= self.position.0 && position.0 < self.position.0 + self.size.0
+            && position.1 >= self.position.1 && position.1 < self.position.1 + self.size.1
+    }
+
+    /// How far `position` is from the line, vertically first.
+    fn distance(&self, position: (f32, f32)) -> (f32, f32) {
+        let distance = |value: f32, start: f32, length: f32| (start - value).max(value - (start + length)).max(0.0);
+        (
+            distance(position.1, self.position.1, self.size.1),
+            distance(position.0, self.position.0, self.size.0),
+        )
+    }
+
+    /// The byte offset in the text closest to `position`, between the glyphs where they were drawn.
+    fn offset_at(&self, position: (f32, f32)) -> usize {
+        let (x, y) = (position.0 - self.position.0, position.1 - self.position.1);
+        let glyphs = spaced_glyphs(&self.buffer, self.align, self.letter_spacing);
+
+        // the line under the pointer, the last one when the pointer is below all of them
+        let Some(line) = glyphs.iter().find(|spaced| y < spaced.line_top + spaced.line_height).or(glyphs.last()) else {
+            return self.text.len();
+        };
+
+        let mut offset = None;
+        for spaced in glyphs.iter().filter(|spaced| spaced.line_top == line.line_top) {
+            // right to left glyphs start at their right edge
+            let (before, after) = match spaced.glyph.level.is_rtl() {
+                true => (spaced.range.end, spaced.range.start),
+                false => (spaced.range.start, spaced.range.end),
+            };
+            if x < spaced.glyph.x + spaced.offset + spaced.glyph.w / 2.0 {
+                offset = Some(before);
+                break;
+            }
+            offset = Some(after);
+        }
+        offset.unwrap_or(self.text.len()).min(self.text.len())
+    }
+}
+
+/// One end of a selection, a byte offset into the text of a line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct SelectionPoint {
+    id: u32,
+    offset: usize,
+}
+
+/// Text selected with the mouse, across the lines of text the renderer drew.
+///
+/// Lines are told apart by the ids of their clay render commands, which stay the same from
+/// frame to frame, and the selection runs from one line to another in the order clay drew
+/// them. Pointer positions are in the same pixels as the layout.
+pub struct UISelection {
+    /// drawn beneath the selected text
+    pub highlight_color: UIColor,
+    lines: Vec<SelectableLine>,
+    anchor: Option<SelectionPoint>,
+    focus: Option<SelectionPoint>,
+    selected: HashMap<u32, Range<usize>>,
+    dragging: bool,
+    last_click: Option<(Instant, (f32, f32))>,
+}
+
+impl UISelection {
+    pub fn new() -> Self {
+        Self {
+            highlight_color: UIColor { r: 0.1, g: 0.3, b: 0.8, a: 0.5 },
+            lines: Vec::new(),
+            anchor: None,
+            focus: None,
+            selected: HashMap::new(),
+            dragging: false,
+            last_click: None,
+        }
+    }
+
+    /// Forgets the lines of the last frame, before the lines of the next one are added.
+    pub fn begin_frame(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Adds a line drawn this frame from `buffer`, as aligned and spaced by `shaping`,
+    /// returning the rectangles to highlight beneath it as x, y, width and height.
+    pub fn add_line(&mut self, id: u32, text: &str, position: (f32, f32), size: (f32, f32), buffer: Rc<Buffer>, shaping: TextShaping) -> Vec<(f32, f32, f32, f32)> {
+        let (align, letter_spacing) = (shaping.align, shaping.letter_spacing);
+        let highlights = self.selected.get(&id)
+            .map(|range| range_rects(&buffer, align, letter_spacing, range.clone()))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(x, y, width, height)| (position.0 + x, position.1 + y, width, height))
+            .collect();
+
+        self.lines.push(SelectableLine {
+            id,
+            text: text.to_string(),
+            position,
+            size,
+            buffer,
+            align,
+            letter_spacing,
+        });
+
+        highlights
+    }
+
+    /// Starts selecting at `position`, or selects the word there on a double click.
+    /// Pressing anywhere but on text clears the selection.
+    pub fn pointer_down(&mut self, position: (f32, f32)) {
+        let now = Instant::now();
+        let double_click = matches!(self.last_click, Some((time, last))
+            if now.duration_since(time) <= DOUBLE_CLICK_TIME
+            && (last.0 - position.0).abs() <= DOUBLE_CLICK_DISTANCE
+            && (last.1 - position.1).abs() <= DOUBLE_CLICK_DISTANCE);
+        // a third click starts over instead of counting as another double click
+        self.last_click = match double_click {
+            true => None,
+            false => Some((now, position)),
+        };
+
+        let Some(line) = self.lines.iter().find(|line| line.contains(position)) else {
+            self.clear();
+            return;
+        };
+
+        let offset = line.offset_at(position);
+        match double_click {
+            true => {
+                let word = word_range(&line.text, offset);
+                self.anchor = Some(SelectionPoint { id: line.id, offset: word.start });
+                self.focus = Some(SelectionPoint { id: line.id, offset: word.end });
+                self.dragging = false;
+            }
+            false => {
+                self.anchor = Some(SelectionPoint { id: line.id, offset });
+                self.focus = self.anchor;
+                self.dragging = true;
+            }
+        }
+        self.update_selected();
+    }
+
+    /// Extends the selection to the text closest to `position` while the pointer is down.
+    pub fn pointer_move(&mut self, position: (f32, f32)) {
+        if !self.dragging {
+            return;
+        }
+
+        let line = self.lines.iter()
+            .min_by(|a, b| a.distance(position).partial_cmp(&b.distance(position)).unwrap());
+        if let Some(line) = line {
+            self.focus = Some(SelectionPoint { id: line.id, offset: line.offset_at(position) });
+            self.update_selected();
+        }
+    }
+
+    pub fn pointer_up(&mut self) {
+        self.dragging = false;
+    }
+
+    pub fn clear(&mut self) {
+        self.anchor = None;
+        self.focus = None;
+        self.dragging = false;
+        self.selected.clear();
+    }
+
+    pub fn has_selection(&self) -> bool {
+        !self.selected.is_empty()
+    }
+
+    /// The selected text, one line of it for every line of text it covers.
+    pub fn selected_text(&self) -> Option<String> {
+        let lines = self.lines.iter()
+            .filter_map(|line| self.selected.get(&line.id).and_then(|range| line.text.get(range.clone())))
+            .collect::<Vec<_>>();
+
+        match lines.is_empty() {
+            true => None,
+            false => Some(lines.join("\n")),
+        }
+    }
+
+    /// Puts the selected text on `clipboard`, returning whether there was any.
+    pub fn copy(&self, clipboard: &mut dyn Clipboard) -> bool {
+        match self.selected_text() {
+            Some(text) => {
+                clipboard.set_text(&text);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Works out the range of every line between the anchor and the focus.
+    fn update_selected(&mut self) {
+        self.selected.clear();
+
+        let (Some(anchor), Some(focus)) = (self.anchor, self.focus) else {
+            return;
+        };
+        let index_of = |point: SelectionPoint| self.lines.iter().position(|line| line.id == point.id);
+        let (Some(anchor_index), Some(focus_index)) = (index_of(anchor), index_of(focus)) else {
+            return;
+        };
+
+        let (start, end) = match (anchor_index, anchor.offset) <= (focus_index, focus.offset) {
+            true => ((anchor_index, anchor.offset), (focus_index, focus.offset)),
+            false => ((focus_index, focus.offset), (anchor_index, anchor.offset)),
+        };
+
+        for index in start.0..=end.0 {
+            let line = &self.lines[index];
+            let from = if index == start.0 { start.1 } else { 0 };
+            let to = if index == end.0 { end.1 } else { line.text.len() };
+            if from < to {
+                self.selected.insert(line.id, from..to);
+            }
+        }
+    }
+}
+
+impl Default for UISelection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The word around `offset`, or the single character there when it is not part of a word.
+fn word_range(text: &str, offset: usize) -> Range<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    // past the end of the line, the word before it is meant
+    let offset = match offset >= text.len() {
+        true => text.char_indices().next_back().map_or(0, |(index, _)| index),
+        false => offset,
+    };
+    let Some(clicked) = text[offset..].chars().next() else {
+        return offset..offset;
+    };
+    if !is_word(clicked) {
+        return offset..offset + clicked.len_utf8();
+    }
+
+    let start = text[..offset].char_indices().rev()
+        .take_while(|(_, c)| is_word(*c))
+        .last()
+        .map_or(offset, |(index, _)| index);
+    let end = text[offset..].char_indices()
+        .take_while(|(_, c)| is_word(*c))
+        .last()
+        .map_or(offset, |(index, c)| offset + index + c.len_utf8());
+
+    start..end
+}
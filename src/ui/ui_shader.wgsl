@@ -1,28 +1,126 @@
-struct Vertex {
-    @location(0)position: vec3<f32>,
-    @location(1)color: vec3<f32>,
-    @location(2)size: vec2<f32>
+struct Instance {
+    @location(0) rect: vec4<f32>,
+    @location(1) radii: vec4<f32>,
+    @location(2) border: vec4<f32>,
+    @location(3) color: vec4<f32>,
+    @location(4) uv: vec4<f32>,
+    @location(5) clip: vec4<f32>,
+    @location(6) depth: f32,
+};
+
+struct Uniforms {
+    screen_size: vec2<f32>,
 };
 
 struct VertexPayload {
     @builtin(position) position: vec4<f32>,
-    @location(0) color: vec3<f32>,
+    // straight alpha, premultiplied in the fragment shader
+    @location(0) color: vec4<f32>,
+    // x, y, width, height in pixels
+    @location(1) rect: vec4<f32>,
+    // top left, top right, bottom right, bottom left
+    @location(2) radii: vec4<f32>,
+    // top, right, bottom, left; all zero fills the shape
+    @location(3) border: vec4<f32>,
+    // u, v, width, height in the image atlas; zero width draws the plain color
+    @location(4) uv: vec4<f32>,
+    // x, y, width, height in pixels, fragments outside are discarded
+    @location(5) clip: vec4<f32>,
 };
 
+@group(0) @binding(0)
+var image_atlas: texture_2d<f32>;
+@group(0) @binding(1)
+var image_sampler: sampler;
+
+@group(1) @binding(0)
+var<uniform> uniforms: Uniforms;
+
 @vertex
-fn vs_main(vertex: Vertex) -> VertexPayload {
+fn vs_main(@builtin(vertex_index) vertex_index: u32, instance: Instance) -> VertexPayload {
+    // top left, bottom left, bottom right, top left, bottom right, top right
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+    );
+
+    // one pixel of margin so the antialiased edge is not cut off
+    let origin = instance.rect.xy - vec2<f32>(1.0);
+    let size = instance.rect.zw + vec2<f32>(2.0);
+    let position = origin + corners[vertex_index] * size;
+
     var out: VertexPayload;
     out.position = vec4<f32>(
-        (vertex.position.x/(vertex.size.x/2.0))-1,
-        -((vertex.position.y/(vertex.size.y/2.0))-1),
-        vertex.position.z, 
+        (position.x/(uniforms.screen_size.x/2.0))-1,
+        -((position.y/(uniforms.screen_size.y/2.0))-1),
+        instance.depth,
         1.0
     );
-    out.color = vertex.color;
+    out.color = instance.color;
+    out.rect = instance.rect;
+    out.radii = instance.radii;
+    out.border = instance.border;
+    out.uv = instance.uv;
+    out.clip = instance.clip;
     return out;
 }
 
+// signed distance to a box with a separate radius per corner, negative inside
+fn rounded_box_distance(point: vec2<f32>, rect: vec4<f32>, radii: vec4<f32>) -> f32 {
+    let half = rect.zw * 0.5;
+    let p = point - (rect.xy + half);
+
+    let top = select(radii.x, radii.y, p.x > 0.0);
+    let bottom = select(radii.w, radii.z, p.x > 0.0);
+    let radius = max(min(select(top, bottom, p.y > 0.0), min(half.x, half.y)), 0.0);
+
+    let q = abs(p) - half + radius;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+}
+
 @fragment
 fn fs_main(in:VertexPayload) -> @location(0) vec4<f32> {
-    return vec4<f32>(in.color, 1.0);
-}
\ No newline at end of file
+    let point = in.position.xy;
+
+    if (point.x < in.clip.x || point.y < in.clip.y || point.x >= in.clip.x + in.clip.z || point.y >= in.clip.y + in.clip.w) {
+        discard;
+    }
+
+    var coverage = clamp(0.5 - rounded_box_distance(point, in.rect, in.radii), 0.0, 1.0);
+
+    if (any(in.border > vec4<f32>(0.0))) {
+        let inner_rect = vec4<f32>(
+            in.rect.x + in.border.w,
+            in.rect.y + in.border.x,
+            in.rect.z - in.border.w - in.border.y,
+            in.rect.w - in.border.x - in.border.z
+        );
+        let inner_radii = max(in.radii - vec4<f32>(
+            max(in.border.x, in.border.w),
+            max(in.border.x, in.border.y),
+            max(in.border.z, in.border.y),
+            max(in.border.z, in.border.w)
+        ), vec4<f32>(0.0));
+
+        if (inner_rect.z > 0.0 && inner_rect.w > 0.0) {
+            coverage *= 1.0 - clamp(0.5 - rounded_box_distance(point, inner_rect, inner_radii), 0.0, 1.0);
+        }
+    }
+
+    if (coverage <= 0.0) {
+        discard;
+    }
+
+    var color = in.color;
+    if (in.uv.z > 0.0) {
+        let local = (point - in.rect.xy) / in.rect.zw;
+        color *= textureSampleLevel(image_atlas, image_sampler, in.uv.xy + local * in.uv.zw, 0.0);
+    }
+
+    let alpha = color.a * coverage;
+    return vec4<f32>(color.rgb * alpha, alpha);
+}
@@ -0,0 +1,386 @@
+use glyphon::Color;
+use image::{Rgba, RgbaImage};
+
+use clay_layout::render_commands::{RenderCommand, RenderCommandConfig};
+use clay_layout::math::Dimensions;
+
+use crate::ui::ui_focus::UIFocusRing;
+use crate::ui::ui_renderer::{srgb_to_linear, text_wrap_width, UIColor, UICornerRadii, UIBorderThickness};
+use crate::ui::ui_text::{decoration_rects, range_rects, spaced_glyphs, MeasureText, TextShaping, TextWrap, UIText};
+
+#[derive(Copy, Clone, Debug)]
+struct ClipRect {
+    left: i32,
+    top: i32,
+    right: i32,
+    bottom: i32,
+}
+
+impl ClipRect {
+    fn intersect(&self, other: &ClipRect) -> ClipRect {
+        ClipRect {
+            left: self.left.max(other.left),
+            top: self.top.max(other.top),
+            right: self.right.min(other.right),
+            bottom: self.bottom.min(other.bottom),
+        }
+    }
+}
+
+/// Rasterizes clay render commands on the cpu, for machines without any wgpu adapter.
+///
+/// Colors are treated the same way the gpu pipeline treats them: rectangle colors
+/// are written as linear values into an srgb target, text colors are srgb.
+///
+/// Images are not drawn, they only exist in the gpu atlas of [`UIImages`], so software
+/// snapshots of layouts with images leave them out.
+///
+/// [`UIImages`]: crate::ui::ui_images::UIImages
+pub struct SoftwareRenderer {
+    pub ui_text: UIText,
+    pub dpi_scale: f32,
+    pub clear_color: UIColor,
+    pub focus_ring: UIFocusRing,
+    width: u32,
+    height: u32,
+    // linear rgba
+    pixels: Vec<[f32; 4]>,
+}
+
+impl SoftwareRenderer {
+    pub fn new(size: (u32, u32)) -> Self {
+        Self {
+            ui_text: UIText::new(),
+            dpi_scale: 1.0,
+            // matches the clear color of the windowed renderer
+            clear_color: UIColor { r: 1.0, g: 0.0, b: 0.0, a: 1.0 },
+            focus_ring: UIFocusRing::default(),
+            width: size.0,
+            height: size.1,
+            pixels: vec![[0.0; 4]; (size.0 * size.1) as usize],
+        }
+    }
+
+    pub fn resize(&mut self, size: (u32, u32)) {
+        self.width = size.0;
+        self.height = size.1;
+        self.pixels = vec![[0.0; 4]; (size.0 * size.1) as usize];
+    }
+
+    pub fn to_image(&self) -> RgbaImage {
+        let mut image = RgbaImage::new(self.width, self.height);
+
+        for (pixel, color) in image.pixels_mut().zip(self.pixels.iter()) {
+            *pixel = Rgba([
+                (linear_to_srgb(color[0]) * 255.0).round() as u8,
+                (linear_to_srgb(color[1]) * 255.0).round() as u8,
+                (linear_to_srgb(color[2]) * 255.0).round() as u8,
+                (color[3] * 255.0).round() as u8,
+            ]);
+        }
+
+        image
+    }
+
+    fn full_clip(&self) -> ClipRect {
+        ClipRect {
+            left: 0,
+            top: 0,
+            right: self.width as i32,
+            bottom: self.height as i32,
+        }
+    }
+
+    fn blend(&mut self, x: i32, y: i32, color: [f32; 4], coverage: f32, clip: &ClipRect) {
+        if x < clip.left || x >= clip.right || y < clip.top || y >= clip.bottom {
+            return;
+        }
+
+        let alpha = color[3] * coverage.clamp(0.0, 1.0);
+        if alpha <= 0.0 {
+            return;
+        }
+
+        let pixel = &mut self.pixels[(y as u32 * self.width + x as u32) as usize];
+        for channel in 0..3 {
+            pixel[channel] = color[channel] * alpha + pixel[channel] * (1.0 - alpha);
+        }
+        pixel[3] = alpha + pixel[3] * (1.0 - alpha);
+    }
+
+    fn pixel_range(&self, position: (f32, f32), size: (f32, f32), clip: &ClipRect) -> ClipRect {
+        ClipRect {
+            left: position.0.floor() as i32,
+            top: position.1.floor() as i32,
+            right: (position.0 + size.0).ceil() as i32,
+            bottom: (position.1 + size.1).ceil() as i32,
+        }.intersect(clip)
+    }
+
+    fn filled_rectangle(&mut self, position: (f32, f32), size: (f32, f32), color: [f32; 4], radii: &UICornerRadii, clip: &ClipRect) {
+        let range = self.pixel_range(position, size, clip);
+
+        for y in range.top..range.bottom {
+            for x in range.left..range.right {
+                let distance = rounded_box_distance((x as f32 + 0.5, y as f32 + 0.5), position, size, radii);
+                self.blend(x, y, color, 0.5 - distance, clip);
+            }
+        }
+    }
+
+    fn rectangle(&mut self, position: (f32, f32), size: (f32, f32), thickness: &UIBorderThickness, color: [f32; 4], radii: &UICornerRadii, clip: &ClipRect) {
+        let range = self.pixel_range(position, size, clip);
+
+        let inner_position = (position.0 + thickness.left, position.1 + thickness.top);
+        let inner_size = (
+            size.0 - thickness.left - thickness.right,
+            size.1 - thickness.top - thickness.bottom,
+        );
+        let inner_radii = UICornerRadii {
+            top_left: (radii.top_left - thickness.top.max(thickness.left)).max(0.0),
+            top_right: (radii.top_right - thickness.top.max(thickness.right)).max(0.0),
+            bottom_left: (radii.bottom_left - thickness.bottom.max(thickness.left)).max(0.0),
+            bottom_right: (radii.bottom_right - thickness.bottom.max(thickness.right)).max(0.0),
+        };
+        let has_inside = inner_size.0 > 0.0 && inner_size.1 > 0.0;
+
+        for y in range.top..range.bottom {
+            for x in range.left..range.right {
+                let point = (x as f32 + 0.5, y as f32 + 0.5);
+                let outer = (0.5 - rounded_box_distance(point, position, size, radii)).clamp(0.0, 1.0);
+                let inner = match has_inside {
+                    true => (0.5 - rounded_box_distance(point, inner_position, inner_size, &inner_radii)).clamp(0.0, 1.0),
+                    false => 0.0
+                };
+                self.blend(x, y, color, outer * (1.0 - inner), clip);
+            }
+        }
+    }
+
+    fn text(&mut self, text: &str, shaping: TextShaping, position: (f32, f32), color: Color, clip: &ClipRect) {
+        let buffer = self.ui_text.shape_with(text, shaping);
+        let (align, letter_spacing) = (shaping.align, shaping.letter_spacing);
+        let square = UICornerRadii { top_left: 0.0, top_right: 0.0, bottom_left: 0.0, bottom_right: 0.0 };
+
+        // span backgrounds go beneath the glyphs
+        for span in self.ui_text.rich_text.spans(text) {
+            let Some(background) = span.style.background else {
+                continue;
+            };
+            let linear = [
+                srgb_to_linear(background.r() as f32 / 255.0),
+                srgb_to_linear(background.g() as f32 / 255.0),
+                srgb_to_linear(background.b() as f32 / 255.0),
+                background.a() as f32 / 255.0,
+            ];
+            for (x, y, width, height) in range_rects(&buffer, align, letter_spacing, span.range) {
+                self.filled_rectangle((position.0 + x, position.1 + y), (width, height), linear, &square, clip);
+            }
+        }
+
+        // the same placement as Buffer::draw, with every glyph moved by its letter spacing
+        let mut pixels = Vec::<(i32, i32, Color)>::new();
+        for spaced in spaced_glyphs(&buffer, align, letter_spacing) {
+            let physical = spaced.glyph.physical((spaced.offset, 0.0), 1.0);
+            let glyph_color = spaced.glyph.color_opt.unwrap_or(color);
+            let line_y = spaced.line_y as i32;
+            self.ui_text.swash_cache.with_pixels(&mut self.ui_text.font_system, physical.cache_key, glyph_color, |x, y, color| {
+                pixels.push((physical.x + x, line_y + physical.y + y, color));
+            });
+        }
+
+        let (left, top) = (position.0.round() as i32, position.1.round() as i32);
+        for (x, y, color) in pixels {
+            let linear = [
+                srgb_to_linear(color.r() as f32 / 255.0),
+                srgb_to_linear(color.g() as f32 / 255.0),
+                srgb_to_linear(color.b() as f32 / 255.0),
+                color.a() as f32 / 255.0,
+            ];
+            self.blend(left + x, top + y, linear, 1.0, clip);
+        }
+
+        for decoration in decoration_rects(&buffer, align, letter_spacing) {
+            let decoration_color = decoration.color.unwrap_or(color);
+            let linear = [
+                srgb_to_linear(decoration_color.r() as f32 / 255.0),
+                srgb_to_linear(decoration_color.g() as f32 / 255.0),
+                srgb_to_linear(decoration_color.b() as f32 / 255.0),
+                decoration_color.a() as f32 / 255.0,
+            ];
+            self.filled_rectangle(
+                (position.0 + decoration.x, position.1 + decoration.y),
+                (decoration.width, decoration.height),
+                linear,
+                &square,
+                clip
+            );
+        }
+    }
+
+    /// Draws the focus ring around an element, over everything else.
+    fn draw_focus_ring(&mut self, position: (f32, f32), size: (f32, f32), radii: &UICornerRadii, clip: &ClipRect) {
+        let ring = self.focus_ring;
+        let grow = ring.offset + ring.width;
+        let outer = |radius: f32| match radius > 0.0 {
+            true => radius + grow,
+            false => 0.0,
+        };
+
+        self.rectangle(
+            (position.0 - grow, position.1 - grow),
+            (size.0 + grow * 2.0, size.1 + grow * 2.0),
+            &UIBorderThickness { top: ring.width, left: ring.width, bottom: ring.width, right: ring.width },
+            [ring.color.r, ring.color.g, ring.color.b, ring.color.a],
+            &UICornerRadii {
+                top_left: outer(radii.top_left),
+                top_right: outer(radii.top_right),
+                bottom_left: outer(radii.bottom_left),
+                bottom_right: outer(radii.bottom_right)
+            },
+            clip
+        );
+    }
+
+    pub fn render_clay<'b>(&mut self, commands: impl IntoIterator<Item = RenderCommand<'b>>) {
+        let clear = [self.clear_color.r, self.clear_color.g, self.clear_color.b, self.clear_color.a];
+        self.pixels.fill(clear);
+
+        let mut scissors = vec![self.full_clip()];
+        // position, size, corner radii and clip of the focused element
+        let mut focused = None;
+
+        for command in commands {
+            let position = (command.bounding_box.x, command.bounding_box.y);
+            let size = (command.bounding_box.width, command.bounding_box.height);
+            let clip = *scissors.last().unwrap();
+
+            if focused.is_none() && self.focus_ring.element_id == Some(command.id) {
+                let radii = match &command.config {
+                    RenderCommandConfig::Rectangle(r) => Some(&r.corner_radii),
+                    RenderCommandConfig::Border(b) => Some(&b.corner_radii),
+                    RenderCommandConfig::Image(i) => Some(&i.corner_radii),
+                    _ => None,
+                };
+                focused = radii.map(|radii| (
+                    position,
+                    size,
+                    UICornerRadii {
+                        top_left: radii.top_left,
+                        top_right: radii.top_right,
+                        bottom_left: radii.bottom_left,
+                        bottom_right: radii.bottom_right
+                    },
+                    clip,
+                ));
+            }
+
+            match command.config {
+                RenderCommandConfig::Rectangle(r) => {
+                    self.filled_rectangle(
+                        position,
+                        size,
+                        [r.color.r/255.0, r.color.g/255.0, r.color.b/255.0, r.color.a/255.0],
+                        &UICornerRadii {
+                            top_left: r.corner_radii.top_left,
+                            top_right: r.corner_radii.top_right,
+                            bottom_left: r.corner_radii.bottom_left,
+                            bottom_right: r.corner_radii.bottom_right
+                        },
+                        &clip
+                    );
+                }
+                RenderCommandConfig::Border(b) => {
+                    self.rectangle(
+                        position,
+                        size,
+                        &UIBorderThickness {
+                            top: b.width.top as f32,
+                            left: b.width.left as f32,
+                            bottom: b.width.bottom as f32,
+                            right: b.width.right as f32
+                        },
+                        [b.color.r/255.0, b.color.g/255.0, b.color.b/255.0, b.color.a/255.0],
+                        &UICornerRadii {
+                            top_left: b.corner_radii.top_left,
+                            top_right: b.corner_radii.top_right,
+                            bottom_left: b.corner_radii.bottom_left,
+                            bottom_right: b.corner_radii.bottom_right
+                        },
+                        &clip
+                    );
+                }
+                RenderCommandConfig::Text(text) => {
+                    let metrics = UIText::metrics(text.font_size as f32, text.line_height as f32, self.dpi_scale);
+                    let shaping = TextShaping::new(text.font_id, metrics)
+                        .wrap(text.wrap_mode.into(), Some(text_wrap_width(size.0)))
+                        .align(text.alignment.into())
+                        .letter_spacing(text.letter_spacing as f32 * self.dpi_scale);
+                    self.text(
+                        text.text,
+                        shaping,
+                        position,
+                        Color::rgba(text.color.r as u8, text.color.g as u8, text.color.b as u8, text.color.a as u8),
+                        &clip
+                    );
+                }
+                RenderCommandConfig::ScissorStart() => {
+                    let scissor = ClipRect {
+                        left: position.0.floor() as i32,
+                        top: position.1.floor() as i32,
+                        right: (position.0 + size.0).ceil() as i32,
+                        bottom: (position.1 + size.1).ceil() as i32,
+                    };
+                    scissors.push(scissor.intersect(&clip));
+                }
+                RenderCommandConfig::ScissorEnd() => {
+                    if scissors.len() > 1 {
+                        scissors.pop();
+                    }
+                }
+                // see the struct docs, there are no images without a device
+                RenderCommandConfig::Image(_) => {}
+                _ => {}
+            }
+        }
+
+        if let Some((position, size, radii, clip)) = focused {
+            self.draw_focus_ring(position, size, &radii, &clip);
+        }
+
+        self.ui_text.rich_text.clear();
+    }
+}
+
+impl MeasureText for SoftwareRenderer {
+    fn measure_text(&mut self, text: &str, font_id: u16, font_size:f32, line_height:f32, letter_spacing:f32, wrap: TextWrap) -> Dimensions {
+        self.ui_text.measure(text, font_id, UIText::metrics(font_size, line_height, self.dpi_scale), letter_spacing * self.dpi_scale, wrap)
+    }
+}
+
+/// Signed distance from `point` to a box with a separate radius per corner, negative inside.
+pub fn rounded_box_distance(point: (f32, f32), position: (f32, f32), size: (f32, f32), radii: &UICornerRadii) -> f32 {
+    let half = (size.0 / 2.0, size.1 / 2.0);
+    let p = (point.0 - (position.0 + half.0), point.1 - (position.1 + half.1));
+
+    let radius = match (p.0 > 0.0, p.1 > 0.0) {
+        (false, false) => radii.top_left,
+        (true, false) => radii.top_right,
+        (false, true) => radii.bottom_left,
+        (true, true) => radii.bottom_right,
+    }.min(half.0).min(half.1).max(0.0);
+
+    let q = (p.0.abs() - half.0 + radius, p.1.abs() - half.1 + radius);
+    let outside = (q.0.max(0.0).powi(2) + q.1.max(0.0).powi(2)).sqrt();
+    let inside = q.0.max(q.1).min(0.0);
+
+    outside + inside - radius
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    match value <= 0.0031308 {
+        true => value * 12.92,
+        false => 1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
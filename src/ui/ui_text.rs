@@ -0,0 +1,535 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::Path;
+use std::rc::Rc;
+
+use glyphon::{Buffer, Color, FontSystem, Metrics, Shaping, SwashCache, Wrap};
+use glyphon::cosmic_text::{Align, Attrs, LayoutGlyph};
+
+use clay_layout::math::Dimensions;
+use clay_layout::text::{TextAlignment, TextConfig, TextElementConfigWrapMode};
+
+use crate::ui::ui_fonts::{self, TextDecoration, UIFont, UIFonts};
+use crate::ui::ui_rich_text::UIRichText;
+
+/// Implemented by every backend that can answer clay's text measurement callback.
+pub trait MeasureText {
+    fn measure_text(&mut self, text: &str, font_id: u16, font_size:f32, line_height:f32, letter_spacing:f32, wrap: TextWrap) -> Dimensions;
+}
+
+/// Clay's text measurement callback, answered by whichever backend is passed as user data:
+///
+/// `clay.set_measure_text_function_user_data(ui_state.clone(), measure_text);`
+pub fn measure_text<T: MeasureText>(text: &str, config: &TextConfig, ui: &mut Rc<RefCell<T>>) -> Dimensions {
+    ui.borrow_mut().measure_text(text, config.font_id, config.font_size as f32, config.line_height as f32, config.letter_spacing as f32, config.wrap_mode.into())
+}
+
+/// How text breaks into lines, mirroring clay's wrap modes.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextWrap {
+    /// Break at newlines, and between words where a line gets wider than the wrap width.
+    #[default]
+    Words,
+    /// Break at newlines only.
+    Newlines,
+    /// Never break, newlines are drawn as spaces.
+    None,
+}
+
+impl From<TextElementConfigWrapMode> for TextWrap {
+    fn from(mode: TextElementConfigWrapMode) -> Self {
+        match mode {
+            TextElementConfigWrapMode::Words => TextWrap::Words,
+            TextElementConfigWrapMode::Newline => TextWrap::Newlines,
+            TextElementConfigWrapMode::None => TextWrap::None,
+        }
+    }
+}
+
+/// Where the lines of a text sit within the width of its element, mirroring clay's text alignment.
+///
+/// Clay already places every line it wraps, so this only moves lines the renderer breaks itself.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl From<TextAlignment> for TextAlign {
+    fn from(alignment: TextAlignment) -> Self {
+        match alignment {
+            TextAlignment::Left => TextAlign::Left,
+            TextAlignment::Center => TextAlign::Center,
+            TextAlignment::Right => TextAlign::Right,
+        }
+    }
+}
+
+impl From<TextAlign> for Align {
+    fn from(align: TextAlign) -> Self {
+        match align {
+            TextAlign::Left => Align::Left,
+            TextAlign::Center => Align::Center,
+            TextAlign::Right => Align::Right,
+        }
+    }
+}
+
+/// Everything text is shaped and spaced with besides the text itself.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextShaping {
+    pub font_id: u16,
+    pub metrics: Metrics,
+    pub wrap: TextWrap,
+    /// lines wider than this wrap with [`TextWrap::Words`], and are aligned within it
+    pub wrap_width: Option<f32>,
+    pub align: TextAlign,
+    /// added between glyphs when drawn, it does not change how the text is shaped
+    pub letter_spacing: f32,
+}
+
+impl TextShaping {
+    /// Left aligned text without letter spacing, wrapping between words but without a width to wrap at.
+    pub fn new(font_id: u16, metrics: Metrics) -> Self {
+        Self {
+            font_id,
+            metrics,
+            wrap: TextWrap::Words,
+            wrap_width: None,
+            align: TextAlign::Left,
+            letter_spacing: 0.0,
+        }
+    }
+
+    pub fn wrap(mut self, wrap: TextWrap, wrap_width: Option<f32>) -> Self {
+        self.wrap = wrap;
+        self.wrap_width = wrap_width;
+        self
+    }
+
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.letter_spacing = letter_spacing;
+        self
+    }
+}
+
+/// Shaped buffers kept around by default, see [`UIText::set_shape_cache_capacity`].
+const DEFAULT_SHAPE_CACHE_CAPACITY: usize = 1024;
+
+/// A span of rich text with its font looked up.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ShapeSpan {
+    range: Range<usize>,
+    font: UIFont,
+    color: Option<Color>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ShapeKey {
+    text: String,
+    font: UIFont,
+    spans: Vec<ShapeSpan>,
+    // f32 bits, so the key can be hashed
+    font_size: u32,
+    line_height: u32,
+    wrap: TextWrap,
+    wrap_width: Option<u32>,
+    align: TextAlign,
+}
+
+impl ShapeKey {
+    fn new(text: &str, font: &UIFont, spans: &[ShapeSpan], shaping: TextShaping) -> Self {
+        Self {
+            text: text.to_string(),
+            font: font.clone(),
+            spans: spans.to_vec(),
+            font_size: shaping.metrics.font_size.to_bits(),
+            line_height: shaping.metrics.line_height.to_bits(),
+            wrap: shaping.wrap,
+            wrap_width: shaping.wrap_width.map(f32::to_bits),
+            align: shaping.align,
+        }
+    }
+}
+
+struct ShapeEntry {
+    buffer: Rc<Buffer>,
+    last_used: u64,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShapeCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+}
+
+/// Font loading, shaping and measurement shared by the gpu and software renderers.
+///
+/// Shaped buffers are cached by their text and metrics, so text that is measured during
+/// layout and drawn afterwards, frame after frame, is only shaped once. When the cache
+/// is full the least recently used buffer is dropped.
+///
+/// Text that is part of rich text declared in [`UIText::rich_text`] is shaped with its spans.
+pub struct UIText {
+    pub font_system: FontSystem,
+    pub swash_cache: SwashCache,
+    pub fonts: UIFonts,
+    pub rich_text: UIRichText,
+    shape_cache: HashMap<ShapeKey, ShapeEntry>,
+    /// the keys of the cache by when they were last used, oldest first
+    shape_cache_recency: BTreeMap<u64, ShapeKey>,
+    shape_cache_capacity: usize,
+    shape_cache_stats: ShapeCacheStats,
+    uses: u64,
+}
+
+impl UIText {
+    pub fn new() -> Self {
+        Self {
+            font_system: FontSystem::new(),
+            swash_cache: SwashCache::new(),
+            fonts: UIFonts::new(),
+            rich_text: UIRichText::new(),
+            shape_cache: HashMap::new(),
+            shape_cache_recency: BTreeMap::new(),
+            shape_cache_capacity: DEFAULT_SHAPE_CACHE_CAPACITY,
+            shape_cache_stats: ShapeCacheStats::default(),
+            uses: 0,
+        }
+    }
+
+    /// Clay reports a line height of 0 when none was configured.
+    pub fn metrics(font_size:f32, line_height:f32, dpi_scale:f32) -> Metrics {
+        Metrics {
+            font_size: font_size * dpi_scale,
+            line_height: match line_height {
+                0.0 => (font_size * 1.5) * dpi_scale,
+                _ => line_height * dpi_scale
+            }
+        }
+    }
+
+    /// See [`ui_fonts::load_font_file`]. Text may fall back to the new faces, so the
+    /// shape cache is cleared.
+    pub fn load_font_file(&mut self, path: impl AsRef<Path>) -> std::io::Result<Vec<String>> {
+        let families = ui_fonts::load_font_file(&mut self.font_system, path)?;
+        self.clear_shape_cache();
+        Ok(families)
+    }
+
+    /// See [`ui_fonts::load_font_data`], clears the shape cache too.
+    pub fn load_font_data(&mut self, data: Vec<u8>) -> Vec<String> {
+        let families = ui_fonts::load_font_data(&mut self.font_system, data);
+        self.clear_shape_cache();
+        families
+    }
+
+    /// Draws and measures text declared with `font_id` in `font`.
+    pub fn register_font(&mut self, font_id: u16, font: UIFont) {
+        self.fonts.register(font_id, font);
+    }
+
+    /// The extent of the shaped text: its widest line by the height of all its lines.
+    /// Empty text is one line high, so empty paragraphs still take up their space.
+    ///
+    /// Clay measures without knowing the width of the container, it breaks words into lines
+    /// itself, so measurement never wraps at a width.
+    pub fn measure(&mut self, text: &str, font_id: u16, metrics: Metrics, letter_spacing: f32, wrap: TextWrap) -> Dimensions {
+        let buffer = self.shape(text, font_id, metrics, wrap, None);
+
+        let mut width: f32 = 0.0;
+        let mut lines = 0;
+        for run in buffer.layout_runs() {
+            width = width.max(run.line_w + spacing_width(run.glyphs.len(), letter_spacing));
+            lines += 1;
+        }
+
+        (width, lines.max(1) as f32 * buffer.metrics().line_height).into()
+    }
+
+    /// Returns the shaped buffer for `text`, shaping it only if it is not cached yet.
+    ///
+    /// With [`TextWrap::Words`] lines wider than `wrap_width` are broken between words,
+    /// or inside a word that does not fit on a line of its own.
+    pub fn shape(&mut self, text: &str, font_id: u16, metrics: Metrics, wrap: TextWrap, wrap_width: Option<f32>) -> Rc<Buffer> {
+        self.shape_with(text, TextShaping::new(font_id, metrics).wrap(wrap, wrap_width))
+    }
+
+    /// Same as [`UIText::shape`], with the lines aligned within the wrap width as well.
+    pub fn shape_with(&mut self, text: &str, shaping: TextShaping) -> Rc<Buffer> {
+        self.uses += 1;
+        let font = self.fonts.get(shaping.font_id).clone();
+        let spans = self.rich_text.spans(text).into_iter()
+            .map(|span| ShapeSpan {
+                font: span.style.font_id.map_or_else(|| font.clone(), |id| self.fonts.get(id).clone()),
+                range: span.range,
+                color: span.style.color,
+            })
+            .collect::<Vec<_>>();
+        let key = ShapeKey::new(text, &font, &spans, shaping);
+
+        if let Some(entry) = self.shape_cache.get_mut(&key) {
+            let key = self.shape_cache_recency.remove(&entry.last_used).unwrap();
+            self.shape_cache_recency.insert(self.uses, key);
+            entry.last_used = self.uses;
+            self.shape_cache_stats.hits += 1;
+            return entry.buffer.clone();
+        }
+        self.shape_cache_stats.misses += 1;
+
+        let text = match shaping.wrap {
+            TextWrap::None => Cow::Owned(text.replace('\n', " ")),
+            _ => Cow::Borrowed(text),
+        };
+
+        let mut buffer = Buffer::new(&mut self.font_system, shaping.metrics);
+        buffer.set_wrap(&mut self.font_system, match shaping.wrap {
+            TextWrap::Words => Wrap::WordOrGlyph,
+            TextWrap::Newlines | TextWrap::None => Wrap::None,
+        });
+        buffer.set_size(&mut self.font_system, shaping.wrap_width, None);
+        match spans.is_empty() {
+            true => buffer.set_text(&mut self.font_system, &text, font.attrs(), Shaping::Advanced),
+            false => buffer.set_rich_text(&mut self.font_system, rich_text_attrs(&text, &font, &spans), font.attrs(), Shaping::Advanced),
+        }
+        // lines are laid out again when shaped, so the alignment only needs setting before that
+        for line in buffer.lines.iter_mut() {
+            line.set_align(Some(shaping.align.into()));
+        }
+        buffer.shape_until_scroll(&mut self.font_system, false);
+        let buffer = Rc::new(buffer);
+
+        while self.shape_cache.len() >= self.shape_cache_capacity.max(1) {
+            self.evict_least_recently_used();
+        }
+        self.shape_cache_recency.insert(self.uses, key.clone());
+        self.shape_cache.insert(key, ShapeEntry {
+            buffer: buffer.clone(),
+            last_used: self.uses,
+        });
+
+        buffer
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, key)) = self.shape_cache_recency.pop_first() {
+            self.shape_cache.remove(&key);
+            self.shape_cache_stats.evictions += 1;
+        }
+    }
+
+    pub fn shape_cache_stats(&self) -> ShapeCacheStats {
+        ShapeCacheStats {
+            entries: self.shape_cache.len(),
+            ..self.shape_cache_stats
+        }
+    }
+
+    pub fn reset_shape_cache_stats(&mut self) {
+        self.shape_cache_stats = ShapeCacheStats::default();
+    }
+
+    /// Sets how many shaped buffers are kept, dropping the least recently used ones over it.
+    pub fn set_shape_cache_capacity(&mut self, capacity: usize) {
+        self.shape_cache_capacity = capacity;
+        while self.shape_cache.len() > capacity {
+            self.evict_least_recently_used();
+        }
+    }
+
+    pub fn clear_shape_cache(&mut self) {
+        self.shape_cache.clear();
+        self.shape_cache_recency.clear();
+    }
+}
+
+impl Default for UIText {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Splits `text` at the spans, the text between them is in `font`.
+fn rich_text_attrs<'a>(text: &'a str, font: &'a UIFont, spans: &'a [ShapeSpan]) -> Vec<(&'a str, Attrs<'a>)> {
+    let mut pieces = Vec::<(&str, Attrs)>::new();
+    let mut end = 0;
+
+    for span in spans {
+        if span.range.start > end {
+            pieces.push((&text[end..span.range.start], font.attrs()));
+        }
+        let attrs = match span.color {
+            Some(color) => span.font.attrs().color(color),
+            None => span.font.attrs(),
+        };
+        pieces.push((&text[span.range.clone()], attrs));
+        end = span.range.end;
+    }
+    if end < text.len() {
+        pieces.push((&text[end..], font.attrs()));
+    }
+
+    pieces
+}
+
+/// A glyph of a shaped buffer, with the line it sits on and how far letter spacing moves it.
+pub struct SpacedGlyph<'a> {
+    pub glyph: &'a LayoutGlyph,
+    /// the bytes of the glyph's cluster in the text the buffer was shaped from
+    pub range: Range<usize>,
+    pub line_y: f32,
+    pub line_top: f32,
+    pub line_height: f32,
+    pub offset: f32,
+}
+
+/// The extra width `letter_spacing` adds to a line of `glyphs` glyphs, it goes between them.
+fn spacing_width(glyphs: usize, letter_spacing: f32) -> f32 {
+    glyphs.saturating_sub(1) as f32 * letter_spacing
+}
+
+/// How far the first glyph of a line moves, so aligned lines grow away from the side
+/// they are aligned against.
+fn spacing_start(align: TextAlign, glyphs: usize, letter_spacing: f32) -> f32 {
+    match align {
+        TextAlign::Left => 0.0,
+        TextAlign::Center => -spacing_width(glyphs, letter_spacing) / 2.0,
+        TextAlign::Right => -spacing_width(glyphs, letter_spacing),
+    }
+}
+
+/// The glyphs of `buffer` with `letter_spacing` added between the glyphs of each line.
+///
+/// cosmic-text has no letter spacing of its own, so the renderers draw every glyph of
+/// spaced text moved by its offset. `align` is the alignment the buffer was shaped with.
+pub fn spaced_glyphs(buffer: &Buffer, align: TextAlign, letter_spacing: f32) -> Vec<SpacedGlyph<'_>> {
+    let mut glyphs = Vec::<SpacedGlyph>::new();
+
+    let mut line_starts = Vec::<usize>::with_capacity(buffer.lines.len());
+    let mut line_start = 0;
+    for line in buffer.lines.iter() {
+        line_starts.push(line_start);
+        line_start += line.text().len() + line.ending().as_str().len();
+    }
+
+    for run in buffer.layout_runs() {
+        let start = spacing_start(align, run.glyphs.len(), letter_spacing);
+        let line_start = line_starts[run.line_i];
+        for (index, glyph) in run.glyphs.iter().enumerate() {
+            glyphs.push(SpacedGlyph {
+                glyph,
+                range: line_start + glyph.start..line_start + glyph.end,
+                line_y: run.line_y,
+                line_top: run.line_top,
+                line_height: run.line_height,
+                offset: start + index as f32 * letter_spacing,
+            });
+        }
+    }
+
+    glyphs
+}
+
+/// The rectangles behind the glyphs of `range` in `buffer`, one for each line, as x, y, width
+/// and height relative to where the buffer is drawn. Glyphs are only covered as a whole.
+pub fn range_rects(buffer: &Buffer, align: TextAlign, letter_spacing: f32, range: Range<usize>) -> Vec<(f32, f32, f32, f32)> {
+    let mut rects = Vec::<(f32, f32, f32, f32)>::new();
+
+    // left, top, right and height of the line being covered
+    let mut line = None::<(f32, f32, f32, f32)>;
+    for spaced in spaced_glyphs(buffer, align, letter_spacing) {
+        if spaced.range.start < range.start || spaced.range.end > range.end {
+            continue;
+        }
+        let left = spaced.glyph.x + spaced.offset;
+        let right = left + spaced.glyph.w;
+        line = match line {
+            Some((line_left, line_top, line_right, line_height)) if line_top == spaced.line_top => {
+                Some((line_left.min(left), line_top, line_right.max(right), line_height))
+            }
+            other => {
+                rects.extend(other);
+                Some((left, spaced.line_top, right, spaced.line_height))
+            }
+        };
+    }
+    rects.extend(line);
+
+    rects.into_iter()
+        .map(|(left, top, right, height)| (left, top, right - left, height))
+        .collect()
+}
+
+/// An underline or strikethrough, relative to where its buffer is drawn. Without a color
+/// of its own it is drawn in the color of the text.
+pub struct DecorationRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub color: Option<Color>,
+}
+
+/// The underlines and strikethroughs of `buffer`, one for each stretch of a line whose glyphs
+/// share their decoration and color.
+pub fn decoration_rects(buffer: &Buffer, align: TextAlign, letter_spacing: f32) -> Vec<DecorationRect> {
+    let mut rects = Vec::<DecorationRect>::new();
+
+    let font_size = buffer.metrics().font_size;
+    let thickness = (font_size / 14.0).max(1.0);
+
+    for run in buffer.layout_runs() {
+        let start = spacing_start(align, run.glyphs.len(), letter_spacing);
+
+        let mut index = 0;
+        while index < run.glyphs.len() {
+            let first = &run.glyphs[index];
+            let mut left = f32::MAX;
+            let mut right = f32::MIN;
+            while index < run.glyphs.len()
+                && run.glyphs[index].metadata == first.metadata
+                && run.glyphs[index].color_opt == first.color_opt
+            {
+                let glyph = &run.glyphs[index];
+                let x = glyph.x + start + index as f32 * letter_spacing;
+                left = left.min(x);
+                right = right.max(x + glyph.w);
+                index += 1;
+            }
+
+            let decoration = TextDecoration::from_metadata(first.metadata);
+            // offsets from the baseline, in the rough places fonts put them
+            if decoration.underline && left < right {
+                rects.push(DecorationRect {
+                    x: left,
+                    y: run.line_y + font_size * 0.1 - thickness / 2.0,
+                    width: right - left,
+                    height: thickness,
+                    color: first.color_opt,
+                });
+            }
+            if decoration.strikethrough && left < right {
+                rects.push(DecorationRect {
+                    x: left,
+                    y: run.line_y - font_size * 0.3 - thickness / 2.0,
+                    width: right - left,
+                    height: thickness,
+                    color: first.color_opt,
+                });
+            }
+        }
+    }
+
+    rects
+}
@@ -0,0 +1,131 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use clay_layout::{fixed, grow, layout::Padding, Clay, Color, Declaration};
+use image::Rgba;
+
+use clay_rs_wgpu::testing::snapshot::{render_snapshot_with_state, SnapshotConfig};
+use clay_rs_wgpu::ui::ui_custom::{CustomElementId, CustomRenderContext, UICustomElements};
+
+const BACKGROUND: Color = Color::rgb(43.0, 41.0, 51.0);
+const WHITE: Color = Color::rgb(255.0, 255.0, 255.0);
+
+/// A custom element scrolled into a shorter container, one for `gone`, and a white square after both.
+fn custom_layout(clay: &Clay, element: CustomElementId, gone: CustomElementId) {
+    clay.with(&Declaration::new()
+        .layout()
+            .width(grow!())
+            .height(grow!())
+            .padding(Padding::all(16))
+            .child_gap(16)
+            .end()
+        .background_color(BACKGROUND)
+        , |_| {
+            clay.with(&Declaration::new()
+                .scroll(false, true)
+                .layout()
+                    .width(fixed!(100.0))
+                    .height(fixed!(60.0))
+                    .end()
+                , |_| {
+                    clay.with(&Declaration::new()
+                        .layout()
+                            .width(fixed!(100.0))
+                            .height(fixed!(200.0))
+                            .end()
+                        .custom()
+                            .data(element.custom_data())
+                            .end()
+                        , |_| {}
+                    );
+                }
+            );
+            clay.with(&Declaration::new()
+                .layout()
+                    .width(fixed!(50.0))
+                    .height(fixed!(50.0))
+                    .end()
+                .custom()
+                    .data(gone.custom_data())
+                    .end()
+                , |_| {}
+            );
+            clay.with(&Declaration::new()
+                .layout()
+                    .width(fixed!(40.0))
+                    .height(fixed!(40.0))
+                    .end()
+                .background_color(WHITE)
+                , |_| {}
+            );
+        }
+    );
+}
+
+#[test]
+fn custom_elements_are_drawn_through_their_callback() {
+    let calls = Rc::new(RefCell::new(Vec::new()));
+
+    let image = render_snapshot_with_state(&SnapshotConfig::default(), |clay, ui_state| {
+        let mut ui = ui_state.borrow_mut();
+        let recorded = calls.clone();
+        let element = ui.custom_elements.register(move |context: &mut CustomRenderContext<'_, '_>| {
+            recorded.borrow_mut().push((context.bounding_box, context.clip));
+            // whatever state the callback leaves behind must not leak into the ui drawn after it
+            context.render_pass.set_scissor_rect(0, 0, 1, 1);
+        });
+        let gone = ui.custom_elements.register(|_: &mut CustomRenderContext<'_, '_>| panic!("unregistered callback was called"));
+        ui.custom_elements.unregister(gone);
+        assert!(ui.custom_elements.is_registered(element));
+        assert!(!ui.custom_elements.is_registered(gone));
+        drop(ui);
+
+        custom_layout(clay, element, gone);
+    });
+
+    assert_eq!(*calls.borrow(), [([16.0, 16.0, 100.0, 200.0], [16.0, 16.0, 100.0, 60.0])]);
+    // the square after the custom elements starts at 16 + 100 + 16 + 50 + 16
+    assert_eq!(*image.get_pixel(218, 36), Rgba([255, 255, 255, 255]));
+}
+
+#[test]
+fn custom_data_names_the_callback() {
+    let mut custom_elements = UICustomElements::new();
+    let element = custom_elements.register(|_: &mut CustomRenderContext<'_, '_>| {});
+
+    assert_eq!(CustomElementId::from_custom_data(element.custom_data()), Some(element));
+    assert!(!element.custom_data().is_null());
+    assert_eq!(CustomElementId::from_custom_data(std::ptr::null_mut()), None);
+}
+
+#[test]
+fn unscrolled_custom_elements_are_clipped_to_themselves() {
+    let clips = Rc::new(RefCell::new(Vec::new()));
+
+    render_snapshot_with_state(&SnapshotConfig::default(), |clay, ui_state| {
+        let recorded = clips.clone();
+        let element = ui_state.borrow_mut().custom_elements.register(move |context: &mut CustomRenderContext<'_, '_>| {
+            recorded.borrow_mut().push((context.bounding_box, context.clip));
+        });
+
+        clay.with(&Declaration::new()
+            .layout()
+                .padding(Padding::all(16))
+                .end()
+            , |_| {
+                clay.with(&Declaration::new()
+                    .layout()
+                        .width(fixed!(50.0))
+                        .height(fixed!(40.0))
+                        .end()
+                    .custom()
+                        .data(element.custom_data())
+                        .end()
+                    , |_| {}
+                );
+            }
+        );
+    });
+
+    assert_eq!(*clips.borrow(), [([16.0, 16.0, 50.0, 40.0], [16.0, 16.0, 50.0, 40.0])]);
+}
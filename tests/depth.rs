@@ -0,0 +1,46 @@
+use clay_layout::{fixed, layout::LayoutDirection::TopToBottom, text::TextConfig, Clay, Color, Declaration};
+use image::Rgba;
+
+use clay_rs_wgpu::testing::snapshot::{render_snapshot, SnapshotConfig};
+
+const COLUMNS: usize = 40;
+const ROWS: usize = 30;
+
+const WHITE: Color = Color::rgb(255.0, 255.0, 255.0);
+
+/// Over a thousand four pixel squares, the last one white, and text after all of them.
+fn many_commands(clay: &Clay) {
+    clay.with(&Declaration::new().layout().direction(TopToBottom).end(), |_| {
+        for row in 0..ROWS {
+            clay.with(&Declaration::new(), |_| {
+                for column in 0..COLUMNS {
+                    let color = match row == ROWS - 1 && column == COLUMNS - 1 {
+                        true => WHITE,
+                        false => Color::rgb(0.0, 0.0, 255.0),
+                    };
+                    clay.with(&Declaration::new()
+                        .layout()
+                            .width(fixed!(4.0))
+                            .height(fixed!(4.0))
+                            .end()
+                        .background_color(color)
+                        , |_| {}
+                    );
+                }
+            });
+        }
+        clay.text("Squirrels", TextConfig::new().font_size(16).line_height(20).color(WHITE).end());
+    });
+}
+
+#[test]
+fn commands_past_the_thousandth_are_drawn() {
+    assert!(COLUMNS * ROWS > 1000);
+
+    let image = render_snapshot(&SnapshotConfig::default(), many_commands);
+
+    // the last square ends at 160, 120
+    assert_eq!(*image.get_pixel(158, 118), Rgba([255, 255, 255, 255]));
+    let text_inked = (120..140).any(|y| (0..160).any(|x| image.get_pixel(x, y).0[0] > 128));
+    assert!(text_inked, "no text drawn after the squares");
+}
@@ -0,0 +1,57 @@
+use clay_rs_wgpu::ui::ui_focus::UIFocus;
+
+/// A frame of three focusable elements, returning the ones that were activated.
+fn frame(focus: &mut UIFocus) -> Vec<u32> {
+    focus.begin_frame();
+    let activated = [10, 20, 30].into_iter().filter(|&id| focus.register(id)).collect();
+    focus.end_frame();
+    activated
+}
+
+#[test]
+fn tab_visits_elements_in_order_and_wraps() {
+    let mut focus = UIFocus::new();
+    frame(&mut focus);
+
+    let mut visited = Vec::new();
+    for _ in 0..4 {
+        focus.focus_next();
+        visited.push(focus.focused().unwrap());
+    }
+
+    assert_eq!(visited, [10, 20, 30, 10]);
+}
+
+#[test]
+fn shift_tab_without_focus_starts_at_the_last_element() {
+    let mut focus = UIFocus::new();
+    frame(&mut focus);
+
+    focus.focus_previous();
+    assert_eq!(focus.focused(), Some(30));
+
+    focus.focus(10);
+    focus.focus_previous();
+    assert_eq!(focus.focused(), Some(30));
+}
+
+#[test]
+fn activation_is_reported_once() {
+    let mut focus = UIFocus::new();
+    frame(&mut focus);
+
+    focus.focus(20);
+    focus.activate();
+
+    assert_eq!(frame(&mut focus), [20]);
+    assert!(frame(&mut focus).is_empty());
+}
+
+#[test]
+fn focus_is_dropped_when_the_element_goes_away() {
+    let mut focus = UIFocus::new();
+    focus.focus(40);
+    frame(&mut focus);
+
+    assert_eq!(focus.focused(), None);
+}
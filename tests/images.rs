@@ -0,0 +1,110 @@
+use clay_layout::{fixed, grow, layout::Padding, Clay, Color, Declaration};
+use image::{Rgba, RgbaImage};
+
+use clay_rs_wgpu::graphics::headless_context::HeadlessContext;
+use clay_rs_wgpu::testing::snapshot::{assert_snapshot_with_state, render_snapshot_with_state, SnapshotConfig};
+use clay_rs_wgpu::ui::ui_images::{ImageId, UIImages};
+
+const BACKGROUND: Color = Color::rgb(43.0, 41.0, 51.0);
+
+/// Four squares of green, blue, white and black.
+fn quadrants() -> RgbaImage {
+    RgbaImage::from_fn(8, 8, |x, y| match (x < 4, y < 4) {
+        (true, true) => Rgba([0, 255, 0, 255]),
+        (false, true) => Rgba([0, 0, 255, 255]),
+        (true, false) => Rgba([255, 255, 255, 255]),
+        (false, false) => Rgba([0, 0, 0, 255]),
+    })
+}
+
+fn image_layout(clay: &Clay, image: ImageId) {
+    clay.with(&Declaration::new()
+        .layout()
+            .width(grow!())
+            .height(grow!())
+            .padding(Padding::all(16))
+            .child_gap(16)
+            .end()
+        .background_color(BACKGROUND)
+        , |_| {
+            for radius in [0.0, 24.0] {
+                clay.with(&Declaration::new()
+                    .layout()
+                        .width(fixed!(96.0))
+                        .height(fixed!(96.0))
+                        .end()
+                    .corner_radius()
+                        .all(radius)
+                        .end()
+                    .image()
+                        .data(image.image_data())
+                        .source_dimensions((8.0, 8.0).into())
+                        .end()
+                    , |_| {}
+                );
+            }
+        }
+    );
+}
+
+#[test]
+fn inserted_images_are_found_until_removed() {
+    let (_, _, device, queue) = HeadlessContext::request_device();
+    let mut images = UIImages::new(&device);
+
+    let first = images.insert(quadrants());
+    let second = images.insert(RgbaImage::from_pixel(16, 32, Rgba([255, 0, 0, 255])));
+    assert_ne!(first, second);
+
+    let first_uv = images.prepare(&queue, first).unwrap();
+    let second_uv = images.prepare(&queue, second).unwrap();
+    assert_eq!(first_uv[2] / second_uv[2], 0.5);
+    assert_eq!(first_uv[3] / second_uv[3], 0.25);
+    // the images may not overlap in the atlas
+    assert!(first_uv[0] + first_uv[2] <= second_uv[0] || second_uv[0] + second_uv[2] <= first_uv[0]
+        || first_uv[1] + first_uv[3] <= second_uv[1] || second_uv[1] + second_uv[3] <= first_uv[1]);
+
+    images.remove(first);
+
+    assert!(!images.contains(first));
+    assert!(images.prepare(&queue, first).is_none());
+    assert_eq!(images.prepare(&queue, second), Some(second_uv));
+    // ids of removed images are not handed out again
+    let third = images.insert(quadrants());
+    assert_ne!(third, first);
+    assert!(images.prepare(&queue, third).is_some());
+}
+
+#[test]
+fn image_data_names_the_image() {
+    let (_, _, device, _) = HeadlessContext::request_device();
+    let mut images = UIImages::new(&device);
+    let image = images.insert(quadrants());
+
+    assert_eq!(ImageId::from_image_data(image.image_data()), Some(image));
+    assert!(!image.image_data().is_null());
+    assert_eq!(ImageId::from_image_data(std::ptr::null_mut()), None);
+}
+
+#[test]
+fn image_elements_draw_their_image_data() {
+    let config = SnapshotConfig::default();
+    let image = render_snapshot_with_state(&config, |clay, ui_state| {
+        let image = ui_state.borrow_mut().images.insert(quadrants());
+        image_layout(clay, image);
+    });
+
+    // the square image element starts at 16,16 and is 96 pixels wide, a quadrant each 48
+    assert_eq!(*image.get_pixel(40, 40), Rgba([0, 255, 0, 255]));
+    assert_eq!(*image.get_pixel(88, 40), Rgba([0, 0, 255, 255]));
+    assert_eq!(*image.get_pixel(40, 88), Rgba([255, 255, 255, 255]));
+    assert_eq!(*image.get_pixel(88, 88), Rgba([0, 0, 0, 255]));
+}
+
+#[test]
+fn snapshot_images() {
+    assert_snapshot_with_state("images", &SnapshotConfig::default(), |clay, ui_state| {
+        let image = ui_state.borrow_mut().images.insert(quadrants());
+        image_layout(clay, image);
+    });
+}
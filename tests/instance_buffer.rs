@@ -0,0 +1,83 @@
+use std::sync::Mutex;
+
+use clay_layout::{fixed, layout::LayoutDirection::TopToBottom, Clay, Color, Declaration};
+use image::Rgba;
+
+use clay_rs_wgpu::graphics::headless_context::HeadlessContext;
+use clay_rs_wgpu::ui::ui_renderer::UIInstance;
+
+const COLUMNS: usize = 80;
+const ROWS: usize = 64;
+
+/// Collects the warnings logged while the test runs.
+struct Warnings(Mutex<Vec<String>>);
+
+impl log::Log for Warnings {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Warn
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static WARNINGS: Warnings = Warnings(Mutex::new(Vec::new()));
+
+/// More four pixel squares than fit into the initial instance buffer, the last one white.
+fn many_rectangles(clay: &Clay) {
+    clay.with(&Declaration::new().layout().direction(TopToBottom).end(), |_| {
+        for row in 0..ROWS {
+            clay.with(&Declaration::new(), |_| {
+                for column in 0..COLUMNS {
+                    let color = match row == ROWS - 1 && column == COLUMNS - 1 {
+                        true => Color::rgb(255.0, 255.0, 255.0),
+                        false => Color::rgb(0.0, 0.0, 255.0),
+                    };
+                    clay.with(&Declaration::new()
+                        .layout()
+                            .width(fixed!(4.0))
+                            .height(fixed!(4.0))
+                            .end()
+                        .background_color(color)
+                        , |_| {}
+                    );
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn instance_buffer_grows_past_its_initial_capacity() {
+    log::set_logger(&WARNINGS).unwrap();
+    log::set_max_level(log::LevelFilter::Warn);
+
+    let mut ctx = HeadlessContext::new((400, 300), 1.0);
+    let initial_capacity = ctx.ui_state.borrow().buffer.size() as usize / std::mem::size_of::<UIInstance>();
+    assert!(initial_capacity < COLUMNS * ROWS);
+
+    let image = ctx.render_layout(many_rectangles);
+
+    let ui = ctx.ui_state.borrow();
+    let capacity = ui.buffer.size() as usize / std::mem::size_of::<UIInstance>();
+    assert!(ui.instance_high_water_mark() >= COLUMNS * ROWS);
+    assert!(capacity >= ui.instance_high_water_mark());
+    // nothing past the initial capacity was dropped
+    assert_eq!(*image.get_pixel(318, 254), Rgba([255, 255, 255, 255]));
+    let reallocations = WARNINGS.0.lock().unwrap().iter()
+        .filter(|warning| warning.starts_with("ui instance buffer full"))
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(reallocations, [format!("ui instance buffer full, reallocating from {} to {} instances", initial_capacity, capacity)]);
+    drop(ui);
+
+    // the grown buffer is kept for the frames after
+    ctx.render_layout(many_rectangles);
+    assert_eq!(ctx.ui_state.borrow().buffer.size() as usize / std::mem::size_of::<UIInstance>(), capacity);
+    assert_eq!(WARNINGS.0.lock().unwrap().iter().filter(|warning| warning.starts_with("ui instance buffer full")).count(), 1);
+}
@@ -0,0 +1,250 @@
+use clay_layout::{elements::FloatingAttachToElement, fixed, grow, layout::Padding, text::TextConfig, Clay, Color, Declaration};
+use image::{Rgba, RgbaImage};
+
+use clay_rs_wgpu::testing::snapshot::{assert_snapshot, assert_software_snapshot, compare_images, render_snapshot, render_software_snapshot, SnapshotConfig};
+
+const BACKGROUND: Color = Color::rgb(43.0, 41.0, 51.0);
+const GREY: Color = Color::rgb(140.0, 140.0, 140.0);
+const WHITE: Color = Color::rgb(255.0, 255.0, 255.0);
+
+fn rounded_rectangles(clay: &Clay) {
+    clay.with(&Declaration::new()
+        .layout()
+            .width(grow!())
+            .height(grow!())
+            .padding(Padding::all(16))
+            .child_gap(16)
+            .end()
+        .background_color(BACKGROUND)
+        , |_| {
+            for radius in [0.0, 8.0, 24.0, 40.0] {
+                clay.with(&Declaration::new()
+                    .layout()
+                        .width(fixed!(80.0))
+                        .height(fixed!(80.0))
+                        .end()
+                    .background_color(GREY)
+                    .corner_radius()
+                        .all(radius)
+                        .end()
+                    , |_| {}
+                );
+            }
+        }
+    );
+}
+
+fn bordered_rectangles(clay: &Clay) {
+    clay.with(&Declaration::new()
+        .layout()
+            .width(grow!())
+            .height(grow!())
+            .padding(Padding::all(16))
+            .child_gap(16)
+            .end()
+        .background_color(BACKGROUND)
+        , |_| {
+            for (radius, thickness) in [(0.0, 1), (8.0, 3), (24.0, 6), (40.0, 12)] {
+                clay.with(&Declaration::new()
+                    .layout()
+                        .width(fixed!(80.0))
+                        .height(fixed!(80.0))
+                        .end()
+                    .corner_radius()
+                        .all(radius)
+                        .end()
+                    .border()
+                        .all_directions(thickness)
+                        .color(WHITE)
+                        .end()
+                    , |_| {}
+                );
+            }
+        }
+    );
+}
+
+fn translucent_overlay(clay: &Clay) {
+    clay.with(&Declaration::new()
+        .layout()
+            .width(grow!())
+            .height(grow!())
+            .padding(Padding::all(16))
+            .end()
+        .background_color(BACKGROUND)
+        , |_| {
+            clay.text(
+                "Text under a dimmed backdrop",
+                TextConfig::new()
+                    .font_size(24)
+                    .color(WHITE)
+                    .end()
+            );
+
+            clay.with(&Declaration::new()
+                .floating()
+                    .attach_to(FloatingAttachToElement::Parent)
+                    .end()
+                .layout()
+                    .width(fixed!(200.0))
+                    .height(fixed!(120.0))
+                    .end()
+                .background_color(Color::rgba(0.0, 0.0, 0.0, 128.0))
+                .corner_radius()
+                    .all(16.0)
+                    .end()
+                , |_| {}
+            );
+        }
+    );
+}
+
+fn nested_scissors(clay: &Clay) {
+    clay.with(&Declaration::new()
+        .layout()
+            .width(grow!())
+            .height(grow!())
+            .padding(Padding::all(16))
+            .end()
+        .background_color(BACKGROUND)
+        , |_| {
+            clay.with(&Declaration::new()
+                .scroll(false, true)
+                .layout()
+                    .width(fixed!(240.0))
+                    .height(fixed!(160.0))
+                    .padding(Padding::all(16))
+                    .end()
+                .background_color(GREY)
+                , |_| {
+                    // taller and wider than both containers, only the overlap may be drawn
+                    clay.with(&Declaration::new()
+                        .scroll(true, false)
+                        .layout()
+                            .width(fixed!(120.0))
+                            .height(fixed!(400.0))
+                            .end()
+                        , |_| {
+                            clay.with(&Declaration::new()
+                                .layout()
+                                    .width(fixed!(300.0))
+                                    .height(fixed!(400.0))
+                                    .end()
+                                .background_color(WHITE)
+                                .corner_radius()
+                                    .all(24.0)
+                                    .end()
+                                .border()
+                                    .all_directions(4)
+                                    .color(BACKGROUND)
+                                    .end()
+                                , |_| {}
+                            );
+                        }
+                    );
+                }
+            );
+        }
+    );
+}
+
+#[test]
+fn identical_images_match() {
+    let image = RgbaImage::from_pixel(4, 4, Rgba([10, 20, 30, 255]));
+    let comparison = compare_images(&image, &image.clone(), 0).unwrap();
+    assert_eq!(comparison.mismatched_pixels, 0);
+}
+
+#[test]
+fn differences_within_tolerance_match() {
+    let expected = RgbaImage::from_pixel(4, 4, Rgba([10, 20, 30, 255]));
+    let mut actual = expected.clone();
+    actual.put_pixel(1, 1, Rgba([12, 20, 30, 255]));
+    actual.put_pixel(2, 2, Rgba([10, 26, 30, 255]));
+
+    let comparison = compare_images(&expected, &actual, 2).unwrap();
+    assert_eq!(comparison.mismatched_pixels, 1);
+    assert_eq!(*comparison.diff.get_pixel(2, 2), Rgba([255, 0, 255, 255]));
+}
+
+#[test]
+fn size_mismatch_is_an_error() {
+    let expected = RgbaImage::new(4, 4);
+    let actual = RgbaImage::new(4, 5);
+    assert!(compare_images(&expected, &actual, 255).is_err());
+}
+
+#[test]
+fn snapshot_rounded_rectangles() {
+    assert_snapshot("rounded_rectangles", &SnapshotConfig::default(), rounded_rectangles);
+}
+
+#[test]
+fn snapshot_bordered_rectangles() {
+    assert_snapshot("bordered_rectangles", &SnapshotConfig::default(), bordered_rectangles);
+}
+
+#[test]
+fn snapshot_translucent_overlay() {
+    let config = SnapshotConfig {
+        tolerance: 8,
+        max_mismatched_pixels: 50,
+        ..Default::default()
+    };
+    assert_snapshot("translucent_overlay", &config, translucent_overlay);
+}
+
+#[test]
+fn snapshot_nested_scissors() {
+    assert_snapshot("nested_scissors", &SnapshotConfig::default(), nested_scissors);
+}
+
+#[test]
+fn snapshot_rounded_rectangles_high_dpi() {
+    let config = SnapshotConfig {
+        size: (800, 600),
+        dpi_scale: 2.0,
+        ..Default::default()
+    };
+    assert_snapshot("rounded_rectangles_high_dpi", &config, rounded_rectangles);
+}
+
+#[test]
+fn odd_sized_target_is_fully_covered() {
+    let config = SnapshotConfig {
+        size: (123, 77),
+        ..Default::default()
+    };
+    let image = render_snapshot(&config, rounded_rectangles);
+
+    assert_eq!(image.dimensions(), (123, 77));
+    // the background fills the whole layout, so none of the red clear color may show
+    for corner in [(0, 0), (122, 0), (0, 76), (122, 76)] {
+        assert_ne!(*image.get_pixel(corner.0, corner.1), Rgba([255, 0, 0, 255]), "clear color at {:?}", corner);
+    }
+}
+
+#[test]
+fn software_snapshot_rounded_rectangles() {
+    assert_software_snapshot("software_rounded_rectangles", &SnapshotConfig::default(), rounded_rectangles);
+}
+
+#[test]
+fn software_snapshot_bordered_rectangles() {
+    assert_software_snapshot("software_bordered_rectangles", &SnapshotConfig::default(), bordered_rectangles);
+}
+
+#[test]
+fn gpu_matches_software_rectangles() {
+    let config = SnapshotConfig::default();
+
+    for layout in [rounded_rectangles, bordered_rectangles, nested_scissors] {
+        let gpu = render_snapshot(&config, layout);
+        let software = render_software_snapshot(&config, layout);
+
+        let comparison = compare_images(&software, &gpu, 8).unwrap();
+        // anti-aliased edges may round differently, but never more than a sliver of the image
+        let allowed = (config.size.0 * config.size.1 / 100) as usize;
+        assert!(comparison.mismatched_pixels <= allowed, "{} pixels differ between backends", comparison.mismatched_pixels);
+    }
+}
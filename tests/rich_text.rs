@@ -0,0 +1,51 @@
+use std::rc::Rc;
+
+use clay_rs_wgpu::ui::ui_fonts::UIFont;
+use clay_rs_wgpu::ui::ui_rich_text::{RichText, SpanStyle, UIRichText};
+use clay_rs_wgpu::ui::ui_text::{TextWrap, UIText};
+
+fn paragraph() -> RichText {
+    RichText::new()
+        .plain("Call ")
+        .span("measure_text", SpanStyle::new().font(1))
+        .plain(" from clay.")
+}
+
+#[test]
+fn slices_of_declared_text_keep_their_spans() {
+    let text = paragraph();
+    let mut rich_text = UIRichText::new();
+    rich_text.declare(&text);
+
+    // the way clay hands out words of a paragraph
+    let spans = rich_text.spans(&text.text()[8..]);
+
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].range, 0..9);
+}
+
+#[test]
+fn equal_text_that_was_not_declared_has_no_spans() {
+    let text = paragraph();
+    let copy = text.text().to_string();
+    let mut rich_text = UIRichText::new();
+    rich_text.declare(&text);
+
+    assert!(rich_text.spans(&copy).is_empty());
+    rich_text.clear();
+    assert!(rich_text.spans(text.text()).is_empty());
+}
+
+#[test]
+fn spans_are_shaped_in_their_own_font() {
+    let mut ui_text = UIText::new();
+    let metrics = UIText::metrics(16.0, 0.0, 1.0);
+    ui_text.register_font(1, UIFont::monospace());
+
+    let text = paragraph();
+    let plain = ui_text.shape(text.text(), 0, metrics, TextWrap::Words, None);
+    ui_text.rich_text.declare(&text);
+    let rich = ui_text.shape(text.text(), 0, metrics, TextWrap::Words, None);
+
+    assert!(!Rc::ptr_eq(&plain, &rich));
+}
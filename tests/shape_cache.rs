@@ -0,0 +1,110 @@
+use std::rc::Rc;
+
+use clay_layout::{fixed, text::TextConfig, Clay, Color, Declaration};
+
+use clay_rs_wgpu::graphics::headless_context::HeadlessContext;
+use clay_rs_wgpu::ui::ui_fonts::UIFont;
+use clay_rs_wgpu::ui::ui_text::{TextWrap, UIText};
+
+#[test]
+fn repeated_text_is_shaped_once() {
+    let mut ui_text = UIText::new();
+    let metrics = UIText::metrics(16.0, 0.0, 1.0);
+
+    let first = ui_text.shape("Squirrels", 0, metrics, TextWrap::Words, None);
+    let second = ui_text.shape("Squirrels", 0, metrics, TextWrap::Words, None);
+
+    assert!(Rc::ptr_eq(&first, &second));
+    let stats = ui_text.shape_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+}
+
+#[test]
+fn metrics_are_part_of_the_key() {
+    let mut ui_text = UIText::new();
+
+    let small = ui_text.shape("Squirrels", 0, UIText::metrics(16.0, 0.0, 1.0), TextWrap::Words, None);
+    let large = ui_text.shape("Squirrels", 0, UIText::metrics(16.0, 0.0, 2.0), TextWrap::Words, None);
+
+    assert!(!Rc::ptr_eq(&small, &large));
+    assert_eq!(ui_text.shape_cache_stats().misses, 2);
+}
+
+#[test]
+fn least_recently_used_text_is_evicted() {
+    let mut ui_text = UIText::new();
+    let metrics = UIText::metrics(16.0, 0.0, 1.0);
+    ui_text.set_shape_cache_capacity(2);
+
+    ui_text.shape("a", 0, metrics, TextWrap::Words, None);
+    ui_text.shape("b", 0, metrics, TextWrap::Words, None);
+    ui_text.shape("a", 0, metrics, TextWrap::Words, None);
+    // evicts "b", which was used longest ago
+    ui_text.shape("c", 0, metrics, TextWrap::Words, None);
+    ui_text.reset_shape_cache_stats();
+
+    ui_text.shape("a", 0, metrics, TextWrap::Words, None);
+    ui_text.shape("b", 0, metrics, TextWrap::Words, None);
+
+    let stats = ui_text.shape_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 2));
+}
+
+#[test]
+fn fonts_are_part_of_the_key() {
+    let mut ui_text = UIText::new();
+    let metrics = UIText::metrics(16.0, 0.0, 1.0);
+    ui_text.register_font(1, UIFont::monospace());
+    ui_text.register_font(2, UIFont::sans_serif().bold());
+
+    let regular = ui_text.shape("Squirrels", 0, metrics, TextWrap::Words, None);
+    let monospace = ui_text.shape("Squirrels", 1, metrics, TextWrap::Words, None);
+    let bold = ui_text.shape("Squirrels", 2, metrics, TextWrap::Words, None);
+    // ids without a registered font fall back to the default face
+    let unregistered = ui_text.shape("Squirrels", 7, metrics, TextWrap::Words, None);
+
+    assert!(!Rc::ptr_eq(&regular, &monospace));
+    assert!(!Rc::ptr_eq(&regular, &bold));
+    assert!(Rc::ptr_eq(&regular, &unregistered));
+}
+
+#[test]
+fn text_moved_to_another_depth_is_not_shaped_again() {
+    let mut ctx = HeadlessContext::new((400, 300), 1.0);
+    ctx.render_layout(|clay| squirrels_after(clay, 0));
+    ctx.ui_state.borrow_mut().ui_text.reset_shape_cache_stats();
+
+    // the squares before the text push it to another depth
+    ctx.render_layout(|clay| squirrels_after(clay, 3));
+
+    assert_eq!(ctx.ui_state.borrow().ui_text.shape_cache_stats().misses, 0);
+}
+
+fn squirrels_after(clay: &Clay, squares: usize) {
+    clay.with(&Declaration::new(), |_| {
+        for _ in 0..squares {
+            clay.with(&Declaration::new()
+                .layout()
+                    .width(fixed!(4.0))
+                    .height(fixed!(4.0))
+                    .end()
+                .background_color(Color::rgb(0.0, 0.0, 255.0))
+                , |_| {}
+            );
+        }
+        clay.text("Squirrels", TextConfig::new().font_size(16).color(Color::rgb(255.0, 255.0, 255.0)).end());
+    });
+}
+
+#[test]
+fn loading_fonts_clears_the_cache() {
+    let mut ui_text = UIText::new();
+    let metrics = UIText::metrics(16.0, 0.0, 1.0);
+    let before = ui_text.shape("Squirrels", 0, metrics, TextWrap::Words, None);
+
+    ui_text.load_font_data(Vec::new());
+
+    assert_eq!(ui_text.shape_cache_stats().entries, 0);
+    let after = ui_text.shape("Squirrels", 0, metrics, TextWrap::Words, None);
+    assert!(!Rc::ptr_eq(&before, &after));
+}
@@ -0,0 +1,59 @@
+use clay_layout::{grow, layout::Padding, text::{TextAlignment, TextConfig, TextElementConfigWrapMode}, Clay, Color, Declaration};
+use image::RgbaImage;
+
+use clay_rs_wgpu::testing::snapshot::{render_snapshot, render_software_snapshot, SnapshotConfig};
+
+const BACKGROUND: Color = Color::rgb(43.0, 41.0, 51.0);
+const WHITE: Color = Color::rgb(255.0, 255.0, 255.0);
+
+/// A long line over a short one, centered, each 20 pixels high starting 16 pixels down.
+fn centered_lines(clay: &Clay, letter_spacing: u16) {
+    clay.with(&Declaration::new()
+        .layout()
+            .width(grow!())
+            .height(grow!())
+            .padding(Padding::all(16))
+            .end()
+        .background_color(BACKGROUND)
+        , |_| {
+            clay.text(
+                "a much longer first line\nshort",
+                TextConfig::new()
+                    .font_size(16)
+                    .line_height(20)
+                    .letter_spacing(letter_spacing)
+                    .color(WHITE)
+                    .wrap_mode(TextElementConfigWrapMode::Newline)
+                    .alignment(TextAlignment::Center)
+                    .end()
+            );
+        }
+    );
+}
+
+/// The leftmost and rightmost column with text in them, within `rows`.
+fn inked_columns(image: &RgbaImage, rows: std::ops::Range<u32>) -> (u32, u32) {
+    let columns = image.enumerate_pixels()
+        .filter(|(_, y, pixel)| rows.contains(y) && pixel.0[0] > 128)
+        .map(|(x, _, _)| x)
+        .collect::<Vec<_>>();
+    (*columns.iter().min().unwrap(), *columns.iter().max().unwrap())
+}
+
+#[test]
+fn centered_lines_are_centered_once() {
+    let config = SnapshotConfig::default();
+
+    for letter_spacing in [0, 4] {
+        let layout = |clay: &Clay| centered_lines(clay, letter_spacing);
+        for image in [render_snapshot(&config, layout), render_software_snapshot(&config, layout)] {
+            let (long_left, long_right) = inked_columns(&image, 16..36);
+            let (short_left, short_right) = inked_columns(&image, 36..56);
+
+            assert!(long_left >= 16, "the long line starts at {}", long_left);
+            assert!(short_left > long_left + 20, "the short line is not centered");
+            let offset = (long_left + long_right) as f32 / 2.0 - (short_left + short_right) as f32 / 2.0;
+            assert!(offset.abs() <= 3.0, "the short line is {} pixels off center with letter spacing {}", offset, letter_spacing);
+        }
+    }
+}
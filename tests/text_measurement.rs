@@ -0,0 +1,56 @@
+use clay_rs_wgpu::ui::ui_text::{TextWrap, UIText};
+
+#[test]
+fn every_line_counts_towards_the_height() {
+    let mut ui_text = UIText::new();
+    let metrics = UIText::metrics(16.0, 20.0, 1.0);
+
+    let size = ui_text.measure("first\nsecond\nthird", 0, metrics, 0.0, TextWrap::Words);
+
+    assert_eq!(size.height, 60.0);
+}
+
+#[test]
+fn the_widest_line_sets_the_width() {
+    let mut ui_text = UIText::new();
+    let metrics = UIText::metrics(16.0, 0.0, 1.0);
+
+    let longest = ui_text.measure("a much longer second line", 0, metrics, 0.0, TextWrap::Words);
+    let size = ui_text.measure("short\na much longer second line\nshort", 0, metrics, 0.0, TextWrap::Words);
+
+    assert_eq!(size.width, longest.width);
+}
+
+#[test]
+fn empty_text_is_one_line_high() {
+    let mut ui_text = UIText::new();
+    let metrics = UIText::metrics(16.0, 20.0, 1.0);
+
+    let size = ui_text.measure("", 0, metrics, 0.0, TextWrap::Words);
+
+    assert_eq!((size.width, size.height), (0.0, 20.0));
+}
+
+#[test]
+fn newlines_break_lines_unless_wrapping_is_off() {
+    let mut ui_text = UIText::new();
+    let metrics = UIText::metrics(16.0, 20.0, 1.0);
+
+    let newlines = ui_text.measure("first\nsecond", 0, metrics, 0.0, TextWrap::Newlines);
+    let unwrapped = ui_text.measure("first\nsecond", 0, metrics, 0.0, TextWrap::None);
+
+    assert_eq!(newlines.height, 40.0);
+    assert_eq!(unwrapped.height, 20.0);
+}
+
+#[test]
+fn letter_spacing_goes_between_glyphs() {
+    let mut ui_text = UIText::new();
+    let metrics = UIText::metrics(16.0, 20.0, 1.0);
+
+    let plain = ui_text.measure("spaced", 0, metrics, 0.0, TextWrap::Words);
+    let spaced = ui_text.measure("spaced", 0, metrics, 2.0, TextWrap::Words);
+
+    assert_eq!(spaced.width, plain.width + 5.0 * 2.0);
+    assert_eq!(spaced.height, plain.height);
+}
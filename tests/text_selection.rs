@@ -0,0 +1,106 @@
+use clay_rs_wgpu::ui::ui_clipboard::{Clipboard, MemoryClipboard};
+use clay_rs_wgpu::ui::ui_selection::UISelection;
+use clay_rs_wgpu::ui::ui_text::{TextShaping, TextWrap, UIText};
+
+/// Two lines of text 20 pixels high, the way the renderer hands them to the selection.
+fn two_lines(ui_text: &mut UIText, selection: &mut UISelection) {
+    let metrics = UIText::metrics(16.0, 20.0, 1.0);
+    for (index, text) in ["squirrels climb trees", "and bury nuts"].iter().enumerate() {
+        let buffer = ui_text.shape(text, 0, metrics, TextWrap::Words, None);
+        let width = ui_text.measure(text, 0, metrics, 0.0, TextWrap::Words).width;
+        selection.add_line(index as u32 + 1, text, (10.0, 10.0 + index as f32 * 20.0), (width, 20.0), buffer, TextShaping::new(0, metrics));
+    }
+}
+
+#[test]
+fn dragging_selects_across_lines() {
+    let mut ui_text = UIText::new();
+    let mut selection = UISelection::new();
+    two_lines(&mut ui_text, &mut selection);
+
+    selection.pointer_down((10.0, 15.0));
+    selection.pointer_move((1000.0, 35.0));
+    selection.pointer_up();
+
+    assert_eq!(selection.selected_text().as_deref(), Some("squirrels climb trees\nand bury nuts"));
+}
+
+#[test]
+fn double_click_selects_a_word() {
+    let mut ui_text = UIText::new();
+    let mut selection = UISelection::new();
+    two_lines(&mut ui_text, &mut selection);
+
+    selection.pointer_down((12.0, 15.0));
+    selection.pointer_up();
+    selection.pointer_down((12.0, 15.0));
+    selection.pointer_up();
+
+    assert_eq!(selection.selected_text().as_deref(), Some("squirrels"));
+}
+
+#[test]
+fn clicking_outside_text_clears_the_selection() {
+    let mut ui_text = UIText::new();
+    let mut selection = UISelection::new();
+    two_lines(&mut ui_text, &mut selection);
+
+    selection.pointer_down((10.0, 15.0));
+    selection.pointer_move((1000.0, 15.0));
+    selection.pointer_up();
+    assert!(selection.has_selection());
+
+    selection.pointer_down((5.0, 100.0));
+
+    assert!(!selection.has_selection());
+}
+
+#[test]
+fn copy_puts_the_selection_on_the_clipboard() {
+    let mut ui_text = UIText::new();
+    let mut selection = UISelection::new();
+    let mut clipboard = MemoryClipboard::new();
+    two_lines(&mut ui_text, &mut selection);
+
+    assert!(!selection.copy(&mut clipboard));
+
+    selection.pointer_down((10.0, 35.0));
+    selection.pointer_move((1000.0, 35.0));
+
+    assert!(selection.copy(&mut clipboard));
+    assert_eq!(clipboard.get_text().as_deref(), Some("and bury nuts"));
+}
+
+#[test]
+fn letter_spaced_text_is_hit_and_highlighted_where_it_is_drawn() {
+    let mut ui_text = UIText::new();
+    let mut selection = UISelection::new();
+    let metrics = UIText::metrics(16.0, 20.0, 1.0);
+    let text = "squirrels";
+    let unspaced = ui_text.measure(text, 0, metrics, 0.0, TextWrap::Words).width;
+    let spaced = ui_text.measure(text, 0, metrics, 10.0, TextWrap::Words).width;
+    let add_line = |ui_text: &mut UIText, selection: &mut UISelection| {
+        let buffer = ui_text.shape(text, 0, metrics, TextWrap::Words, None);
+        selection.add_line(1, text, (10.0, 10.0), (spaced, 20.0), buffer, TextShaping::new(0, metrics).letter_spacing(10.0))
+    };
+    add_line(&mut ui_text, &mut selection);
+
+    // just past where the text would end without its spacing, the spaced glyphs go on
+    selection.pointer_down((10.0, 15.0));
+    selection.pointer_move((10.0 + unspaced + 1.0, 15.0));
+    selection.pointer_up();
+    let selected = selection.selected_text().unwrap();
+    assert!(!selected.is_empty() && selected.len() < text.len(), "selected {:?}", selected);
+
+    selection.pointer_down((10.0, 15.0));
+    selection.pointer_move((1000.0, 15.0));
+    selection.pointer_up();
+    assert_eq!(selection.selected_text().as_deref(), Some(text));
+
+    selection.begin_frame();
+    let highlights = add_line(&mut ui_text, &mut selection);
+    assert_eq!(highlights.len(), 1);
+    let (x, y, width, height) = highlights[0];
+    assert_eq!((x, y, height), (10.0, 10.0, 20.0));
+    assert!((width - spaced).abs() < 0.5, "highlight {} wide, text {} wide", width, spaced);
+}
@@ -0,0 +1,62 @@
+use clay_layout::{grow, layout::Padding, text::{TextConfig, TextElementConfigWrapMode}, Clay, Color, Declaration};
+use image::RgbaImage;
+
+use clay_rs_wgpu::testing::snapshot::{render_snapshot, render_software_snapshot, SnapshotConfig};
+
+const BACKGROUND: Color = Color::rgb(43.0, 41.0, 51.0);
+const WHITE: Color = Color::rgb(255.0, 255.0, 255.0);
+
+/// Two lines of 20 pixels each, starting 16 pixels down.
+fn two_lines(clay: &Clay, mode: TextElementConfigWrapMode) {
+    clay.with(&Declaration::new()
+        .layout()
+            .width(grow!())
+            .height(grow!())
+            .padding(Padding::all(16))
+            .end()
+        .background_color(BACKGROUND)
+        , |_| {
+            clay.text(
+                "first\nsecond",
+                TextConfig::new()
+                    .font_size(16)
+                    .line_height(20)
+                    .color(WHITE)
+                    .wrap_mode(mode)
+                    .end()
+            );
+        }
+    );
+}
+
+/// The first and last row with text in them.
+fn inked_rows(image: &RgbaImage) -> (u32, u32) {
+    let rows = image.enumerate_rows()
+        .filter(|(_, row)| row.clone().any(|(_, _, pixel)| pixel.0[0] > 128))
+        .map(|(y, _)| y)
+        .collect::<Vec<_>>();
+    (*rows.first().unwrap(), *rows.last().unwrap())
+}
+
+#[test]
+fn unwrapped_text_stays_on_the_line_it_was_measured_on() {
+    let config = SnapshotConfig::default();
+    let layout = |clay: &Clay| two_lines(clay, TextElementConfigWrapMode::None);
+
+    for image in [render_snapshot(&config, layout), render_software_snapshot(&config, layout)] {
+        let (top, bottom) = inked_rows(&image);
+        assert!(top >= 16 && bottom < 36, "text drawn in rows {}..={}", top, bottom);
+    }
+}
+
+#[test]
+fn newline_wrapped_text_breaks_at_its_newline() {
+    let config = SnapshotConfig::default();
+    let layout = |clay: &Clay| two_lines(clay, TextElementConfigWrapMode::Newline);
+
+    for image in [render_snapshot(&config, layout), render_software_snapshot(&config, layout)] {
+        let (top, bottom) = inked_rows(&image);
+        assert!(top >= 16 && top < 36, "first line drawn from row {}", top);
+        assert!(bottom >= 36 && bottom < 56, "second line drawn down to row {}", bottom);
+    }
+}